@@ -1,4 +1,5 @@
 // Chronos: Time Machine
+pub mod retention;
 pub mod watcher;
 pub mod storage;
 
@@ -0,0 +1,79 @@
+use crate::config::RetentionConfig;
+use std::collections::HashSet;
+
+const HOUR: i64 = 60 * 60 * 1000;
+const DAY: i64 = 24 * HOUR;
+const WEEK: i64 = 7 * DAY;
+
+/// Which of `snapshots` the policy keeps at `now` (epoch millis). They are
+/// `(path, timestamp)` pairs sorted by path, then timestamp, as the store
+/// lists them.
+pub fn keep(config: &RetentionConfig, snapshots: &[(&str, i64)], now: i64) -> Vec<bool> {
+    let keep_all = i64::from(config.keep_all_hours) * HOUR;
+    let hourly = i64::from(config.hourly_days) * DAY;
+    let daily = i64::from(config.daily_weeks) * WEEK;
+
+    let mut keep = vec![false; snapshots.len()];
+    // (path, tier, bucket) already holding a newer snapshot
+    let mut buckets = HashSet::new();
+    for (i, &(path, timestamp)) in snapshots.iter().enumerate().rev() {
+        let latest = snapshots
+            .get(i + 1)
+            .map(|(p, _)| *p != path)
+            .unwrap_or(true);
+        let age = now - timestamp;
+        let bucket = if age < keep_all {
+            keep[i] = true;
+            continue;
+        } else if age < hourly {
+            Some((0, timestamp.div_euclid(HOUR)))
+        } else if age < daily {
+            Some((1, timestamp.div_euclid(DAY)))
+        } else {
+            None
+        };
+        let first_in_bucket = bucket
+            .map(|(tier, bucket)| buckets.insert((path, tier, bucket)))
+            .unwrap_or(false);
+        keep[i] = latest || first_in_bucket;
+    }
+    keep
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tiers() {
+        let config = RetentionConfig::default();
+        let now = 100 * WEEK;
+        let snapshots = [
+            ("a.rs", now - 6 * WEEK),            // past every tier
+            ("a.rs", now - 3 * WEEK - 2 * HOUR), // daily, same day as the next
+            ("a.rs", now - 3 * WEEK - HOUR),
+            ("a.rs", now - 2 * DAY - 30 * 60 * 1000), // hourly, same hour as the next
+            ("a.rs", now - 2 * DAY - 20 * 60 * 1000),
+            ("a.rs", now - 2 * HOUR), // keep all
+            ("a.rs", now - HOUR),
+            ("b.rs", now - 10 * WEEK), // too old, but the latest of b.rs
+        ];
+        let kept = keep(&config, &snapshots, now);
+        assert_eq!(
+            kept,
+            vec![false, false, true, false, true, true, true, true]
+        );
+    }
+
+    #[test]
+    fn test_everything_expires_but_the_latest() {
+        let config = RetentionConfig {
+            keep_all_hours: 0,
+            hourly_days: 0,
+            daily_weeks: 0,
+            max_size: None,
+        };
+        let snapshots = [("a.rs", 1), ("a.rs", 2), ("a.rs", 3)];
+        assert_eq!(keep(&config, &snapshots, 10), vec![false, false, true]);
+    }
+}
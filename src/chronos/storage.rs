@@ -1,159 +1,857 @@
-use anyhow::Result;
+use crate::chronos::retention;
+use crate::config::RetentionConfig;
+use anyhow::{anyhow, bail, Result};
 use serde::{Deserialize, Serialize};
-use sled::Db;
+use sha2::{Digest, Sha256};
+use sled::transaction::{ConflictableTransactionError, TransactionError, Transactional};
+use sled::{Batch, Db, IVec, Tree};
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+
+/// Layout of the store, kept under `SCHEMA_KEY` in the default tree.
+///
+/// 1. Everything in the default tree under decimal string keys:
+///    `path:ts`, `__time_idx__:ts:path`, `__blob__:hash`, `__blob_refs__:hash`.
+/// 2. One tree per record kind, keyed by binary timestamps (see `ts_bytes`).
+const SCHEMA_VERSION: u32 = 2;
+const SCHEMA_KEY: &[u8] = b"__chronos_schema__";
+
+/// `path \0 ts` -> JSON `SnapshotRef`. Paths never contain NUL, so all the
+/// snapshots of a file are one contiguous range.
+const SNAPSHOTS_TREE: &str = "snapshots";
+/// `ts path` -> empty, every snapshot in time order.
+const TIMELINE_TREE: &str = "timeline";
+/// sha256 hex -> content, shared by every snapshot with the same content.
+const BLOBS_TREE: &str = "blobs";
+/// sha256 hex -> big-endian u64 count of the snapshots and deltas referring
+/// to a blob. The blob is deleted when it drops to zero.
+const BLOB_REFS_TREE: &str = "blob_refs";
+
+const V1_BLOB_PREFIX: &[u8] = b"__blob__:";
+const V1_BLOB_REFS_PREFIX: &[u8] = b"__blob_refs__:";
+const V1_TIME_PREFIX: &[u8] = b"__time_idx__:";
+
+/// Header of a blob stored as a delta: `delta:<depth>:<base sha256>\n`,
+/// followed by a zstd frame compressed with the base content as dictionary.
+/// A delta holds a reference on its base blob. Full blobs are bare zstd
+/// frames.
+const DELTA_TAG: &[u8] = b"delta:";
+/// Longest delta chain before a full keyframe is written, bounding the number
+/// of blobs decoded to restore one snapshot.
+const KEYFRAME_INTERVAL: u32 = 16;
+/// Below this size a delta saves nothing worth a chain.
+const MIN_DELTA_BASE: usize = 512;
+/// zstd would load a base starting with this as a trained dictionary.
+const ZSTD_DICT_MAGIC: [u8; 4] = [0x37, 0xA4, 0x30, 0xEC];
 
 #[derive(Clone)]
 pub struct ChronosStore {
     db: Arc<Db>,
+    snapshots: Tree,
+    timeline: Tree,
+    blobs: Tree,
+    blob_refs: Tree,
+    /// Serialises read-modify-write of reference counts between writers.
+    write_lock: Arc<Mutex<()>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SnapshotInfo {
     pub timestamp: i64,
     pub size: usize,
+    /// Shared by the files the watcher saved together.
+    #[serde(default)]
+    pub change_set: Option<u64>,
 }
 
-impl ChronosStore {
-    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let db = sled::open(path)?;
-        Ok(Self { db: Arc::new(db) })
+/// Value of a snapshot entry, pointing at the blob with its content.
+#[derive(Debug, Serialize, Deserialize)]
+struct SnapshotRef {
+    hash: String,
+    /// Uncompressed size of the content.
+    size: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    change_set: Option<u64>,
+}
+
+impl SnapshotRef {
+    fn decode(value: &[u8]) -> Result<Self> {
+        serde_json::from_slice(value).map_err(|e| anyhow!("Corrupt snapshot entry: {}", e))
     }
 
-    pub fn save_snapshot(&self, file_path: &str, content: &[u8]) -> Result<()> {
-        let compressed = zstd::encode_all(content, 0)?;
-        let timestamp = chrono::Utc::now().timestamp_millis();
+    /// v1 values written before blobs existed hold zstd frames, never JSON.
+    fn decode_v1(value: &[u8]) -> Option<Self> {
+        if value.first() != Some(&b'{') {
+            return None;
+        }
+        serde_json::from_slice(value).ok()
+    }
+}
+
+/// Big-endian with the sign bit flipped, so that byte order is numeric order
+/// for negative timestamps too.
+fn ts_bytes(timestamp: i64) -> [u8; 8] {
+    ((timestamp as u64) ^ (1 << 63)).to_be_bytes()
+}
+
+fn ts_from_bytes(bytes: &[u8]) -> Option<i64> {
+    let bytes: [u8; 8] = bytes.try_into().ok()?;
+    Some((u64::from_be_bytes(bytes) ^ (1 << 63)) as i64)
+}
+
+fn snapshot_prefix(file_path: &str) -> Vec<u8> {
+    let mut key = file_path.as_bytes().to_vec();
+    key.push(0);
+    key
+}
+
+fn snapshot_key(file_path: &str, timestamp: i64) -> Vec<u8> {
+    let mut key = snapshot_prefix(file_path);
+    key.extend_from_slice(&ts_bytes(timestamp));
+    key
+}
+
+fn parse_snapshot_key(key: &[u8]) -> Option<(String, i64)> {
+    let (path, ts) = key.split_at_checked(key.len().checked_sub(9)?)?;
+    let (0, ts) = ts.split_first()? else {
+        return None;
+    };
+    Some((String::from_utf8(path.to_vec()).ok()?, ts_from_bytes(ts)?))
+}
+
+fn timeline_key(timestamp: i64, file_path: &str) -> Vec<u8> {
+    let mut key = ts_bytes(timestamp).to_vec();
+    key.extend_from_slice(file_path.as_bytes());
+    key
+}
+
+fn parse_timeline_key(key: &[u8]) -> Option<(i64, String)> {
+    let (ts, path) = key.split_at_checked(8)?;
+    Some((ts_from_bytes(ts)?, String::from_utf8(path.to_vec()).ok()?))
+}
+
+fn content_hash(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+struct DeltaHeader {
+    depth: u32,
+    base: String,
+    /// Offset of the zstd frame in the blob.
+    data: usize,
+}
+
+impl DeltaHeader {
+    fn parse(blob: &[u8]) -> Option<Self> {
+        let rest = blob.strip_prefix(DELTA_TAG)?;
+        let end = rest.iter().position(|b| *b == b'\n')?;
+        let header = std::str::from_utf8(&rest[..end]).ok()?;
+        let (depth, base) = header.split_once(':')?;
+        Some(Self {
+            depth: depth.parse().ok()?,
+            base: base.to_string(),
+            data: DELTA_TAG.len() + end + 1,
+        })
+    }
+}
+
+/// Chain depth of a blob, 0 for keyframes.
+fn blob_depth(blob: &[u8]) -> u32 {
+    DeltaHeader::parse(blob).map(|h| h.depth).unwrap_or(0)
+}
+
+fn compress_delta(content: &[u8], base: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = zstd::stream::Encoder::with_dictionary(Vec::new(), 0, base)?;
+    encoder.write_all(content)?;
+    Ok(encoder.finish()?)
+}
+
+fn decompress_delta(data: &[u8], base: &[u8]) -> Result<Vec<u8>> {
+    let mut decoder = zstd::stream::Decoder::with_dictionary(data, base)?;
+    let mut content = Vec::new();
+    decoder.read_to_end(&mut content)?;
+    Ok(content)
+}
+
+/// Space used by the store, from `ChronosStore::stats`.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct StorageStats {
+    pub snapshots: usize,
+    pub full_blobs: usize,
+    pub delta_blobs: usize,
+    /// Uncompressed size of every snapshot.
+    pub logical_bytes: u64,
+    /// Bytes actually held in blobs.
+    pub stored_bytes: u64,
+}
+
+impl StorageStats {
+    /// How many times smaller the store is than the plain snapshots.
+    pub fn ratio(&self) -> f64 {
+        if self.stored_bytes == 0 {
+            1.0
+        } else {
+            self.logical_bytes as f64 / self.stored_bytes as f64
+        }
+    }
+}
+
+/// What `ChronosStore::gc` removed, or would remove on a dry run.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct GcReport {
+    /// Pruned snapshots as (path, timestamp), by path then time.
+    pub pruned: Vec<(String, i64)>,
+    pub freed_blobs: usize,
+    /// Blobs that no snapshot needed even before pruning.
+    pub orphaned_blobs: usize,
+    pub freed_bytes: u64,
+    /// Blob bytes before the collection.
+    pub stored_bytes: u64,
+}
+
+/// Takes a reference on `hash` and, the first time, on its delta bases.
+fn add_ref(refs: &mut HashMap<String, u64>, bases: &HashMap<String, Option<String>>, hash: &str) {
+    let mut next = Some(hash.to_string());
+    while let Some(hash) = next.take() {
+        let count = refs.entry(hash.clone()).or_default();
+        *count += 1;
+        if *count == 1 {
+            next = bases.get(&hash).cloned().flatten();
+        }
+    }
+}
+
+/// Drops a reference on `hash`, releasing its bases when it reaches zero.
+/// Returns the blobs freed.
+fn release_ref(
+    refs: &mut HashMap<String, u64>,
+    bases: &HashMap<String, Option<String>>,
+    hash: &str,
+) -> Vec<String> {
+    let mut freed = Vec::new();
+    let mut next = Some(hash.to_string());
+    while let Some(hash) = next.take() {
+        let Some(count) = refs.get_mut(&hash) else {
+            break;
+        };
+        *count -= 1;
+        if *count == 0 {
+            refs.remove(&hash);
+            next = bases.get(&hash).cloned().flatten();
+            freed.push(hash);
+        }
+    }
+    freed
+}
 
-        // 1. Primary Data Key: "file_path:timestamp" (Existing format)
-        let data_key = format!("{}:{}", file_path, timestamp);
+fn decode_count(value: Option<IVec>) -> u64 {
+    value
+        .and_then(|v| v.as_ref().try_into().ok())
+        .map(u64::from_be_bytes)
+        .unwrap_or(0)
+}
+
+/// Writes of one operation, applied to every tree in a single transaction.
+#[derive(Default)]
+struct Changes {
+    snapshots: Batch,
+    timeline: Batch,
+    blobs: Batch,
+    blob_refs: Batch,
+    /// Counts raised by staged snapshots, so several in one operation see
+    /// each other. Written to `blob_refs` on apply.
+    refs: HashMap<String, u64>,
+}
 
-        // 2. Time Index Key: "__time_idx__:timestamp:file_path"
-        let time_key = format!("__time_idx__:{}:{}", timestamp, file_path);
+/// Files written together by `ChronosStore::save_change_set`.
+#[derive(Debug, Clone)]
+pub struct ChangeSet {
+    pub id: u64,
+    /// Files that got a new snapshot.
+    pub saved: Vec<String>,
+    /// Files identical to their previous snapshot.
+    pub unchanged: Vec<String>,
+}
 
-        // Use a batch to ensure atomicity
-        let mut batch = sled::Batch::default();
-        batch.insert(data_key.as_bytes(), compressed.as_slice());
-        batch.insert(time_key.as_bytes(), &[]); // Empty value for index
-        self.db.apply_batch(batch)?;
+impl ChronosStore {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_db(sled::open(path)?)
+    }
 
+    fn from_db(db: Db) -> Result<Self> {
+        let store = Self {
+            snapshots: db.open_tree(SNAPSHOTS_TREE)?,
+            timeline: db.open_tree(TIMELINE_TREE)?,
+            blobs: db.open_tree(BLOBS_TREE)?,
+            blob_refs: db.open_tree(BLOB_REFS_TREE)?,
+            db: Arc::new(db),
+            write_lock: Arc::new(Mutex::new(())),
+        };
+        store.migrate()?;
+        Ok(store)
+    }
+
+    /// Brings a store written by an older sgit up to `SCHEMA_VERSION`.
+    fn migrate(&self) -> Result<()> {
+        let version = match self.db.get(SCHEMA_KEY)? {
+            Some(value) => u32::from_be_bytes(
+                value
+                    .as_ref()
+                    .try_into()
+                    .map_err(|_| anyhow!("Corrupt Chronos schema version"))?,
+            ),
+            None => 1,
+        };
+        if version > SCHEMA_VERSION {
+            bail!(
+                "Chronos store uses schema {}, this sgit only knows up to {}",
+                version,
+                SCHEMA_VERSION
+            );
+        }
+        if version == SCHEMA_VERSION {
+            return Ok(());
+        }
+
+        self.migrate_v1()?;
+        self.db.insert(SCHEMA_KEY, &SCHEMA_VERSION.to_be_bytes())?;
+        self.db.flush()?;
+        // The v1 keys only go once the new layout is durable
+        for key in self.db.iter().keys() {
+            let key = key?;
+            if key != SCHEMA_KEY {
+                self.db.remove(key)?;
+            }
+        }
         Ok(())
     }
 
-    pub fn get_history(&self, file_path: &str) -> Result<Vec<SnapshotInfo>> {
-        let prefix = format!("{}:", file_path);
-        let mut snapshots = Vec::new();
+    /// Copies the v1 records into the trees. Starts from empty trees, so an
+    /// interrupted migration is simply run again.
+    fn migrate_v1(&self) -> Result<()> {
+        for tree in [
+            &self.snapshots,
+            &self.timeline,
+            &self.blobs,
+            &self.blob_refs,
+        ] {
+            tree.clear()?;
+        }
 
-        for item in self.db.scan_prefix(prefix.as_bytes()) {
+        let mut refs: HashMap<String, u64> = HashMap::new();
+        for item in self.db.iter() {
             let (key, value) = item?;
-            let key_str = String::from_utf8_lossy(&key);
-
-            // Extract timestamp from key after the last colon
-            if let Some(ts_str) = key_str.rsplit(':').next() {
-                if let Ok(ts) = ts_str.parse::<i64>() {
-                    snapshots.push(SnapshotInfo {
-                        timestamp: ts,
-                        size: value.len(),
-                    });
+            if let Some(hash) = key.strip_prefix(V1_BLOB_PREFIX) {
+                self.blobs.insert(hash, value)?;
+                continue;
+            }
+            // Counts and the time index are rebuilt from the snapshots
+            if key.starts_with(V1_BLOB_REFS_PREFIX)
+                || key.starts_with(V1_TIME_PREFIX)
+                || key == SCHEMA_KEY
+            {
+                continue;
+            }
+
+            // `path:ts`, the path may contain ':' itself
+            let key = String::from_utf8_lossy(&key);
+            let Some((path, ts)) = key.rsplit_once(':') else {
+                continue;
+            };
+            let Ok(ts) = ts.parse::<i64>() else {
+                continue;
+            };
+            let snapshot = match SnapshotRef::decode_v1(&value) {
+                Some(snapshot) => snapshot,
+                None => {
+                    // Content stored inline, before blobs existed
+                    let Ok(content) = zstd::decode_all(value.as_ref()) else {
+                        continue;
+                    };
+                    let hash = content_hash(&content);
+                    if !self.blobs.contains_key(&hash)? {
+                        self.blobs.insert(hash.as_bytes(), value)?;
+                    }
+                    SnapshotRef {
+                        hash,
+                        size: content.len(),
+                        change_set: None,
+                    }
                 }
+            };
+            *refs.entry(snapshot.hash.clone()).or_default() += 1;
+            self.snapshots
+                .insert(snapshot_key(path, ts), serde_json::to_vec(&snapshot)?)?;
+            self.timeline.insert(timeline_key(ts, path), &[])?;
+        }
+
+        for item in self.blobs.iter().values() {
+            if let Some(header) = DeltaHeader::parse(&item?) {
+                *refs.entry(header.base).or_default() += 1;
             }
         }
+        for (hash, count) in refs {
+            self.blob_refs
+                .insert(hash.as_bytes(), &count.to_be_bytes())?;
+        }
+        Ok(())
+    }
 
-        // Sort by timestamp descending (newest first)
-        snapshots.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, ()>> {
+        self.write_lock
+            .lock()
+            .map_err(|_| anyhow!("Chronos store lock poisoned"))
+    }
 
-        Ok(snapshots)
+    fn apply(&self, mut changes: Changes) -> Result<()> {
+        for (hash, count) in &changes.refs {
+            changes
+                .blob_refs
+                .insert(hash.as_bytes(), &count.to_be_bytes());
+        }
+        (
+            &self.snapshots,
+            &self.timeline,
+            &self.blobs,
+            &self.blob_refs,
+        )
+            .transaction(|(snapshots, timeline, blobs, blob_refs)| {
+                snapshots.apply_batch(&changes.snapshots)?;
+                timeline.apply_batch(&changes.timeline)?;
+                blobs.apply_batch(&changes.blobs)?;
+                blob_refs.apply_batch(&changes.blob_refs)?;
+                Ok::<_, ConflictableTransactionError<()>>(())
+            })
+            .map_err(|e: TransactionError<()>| anyhow!("Chronos transaction failed: {:?}", e))
     }
 
-    pub fn get_snapshot(&self, file_path: &str, timestamp: i64) -> Result<Option<Vec<u8>>> {
-        let key = format!("{}:{}", file_path, timestamp);
-        if let Some(compressed) = self.db.get(key.as_bytes())? {
-            let content = zstd::decode_all(compressed.as_ref())?;
-            Ok(Some(content))
-        } else {
-            Ok(None)
+    fn refs(&self, hash: &str) -> Result<u64> {
+        Ok(decode_count(self.blob_refs.get(hash)?))
+    }
+
+    /// Count of `hash` once `changes` are applied.
+    fn staged_refs(&self, changes: &Changes, hash: &str) -> Result<u64> {
+        match changes.refs.get(hash) {
+            Some(count) => Ok(*count),
+            None => self.refs(hash),
         }
     }
 
-    /// Retrieve a timeline of all file changes across the repository.
-    /// Returns a list of (timestamp, file_path) tuples, sorted newest first.
-    pub fn get_global_timeline(&self, limit: usize) -> Result<Vec<(i64, String)>> {
-        let prefix = "__time_idx__:";
-        let mut events = Vec::new();
+    /// Records `content` as the current state of `file_path`. Nothing is
+    /// written if it equals the previous snapshot of the same file; returns
+    /// whether a snapshot was added.
+    pub fn save_snapshot(&self, file_path: &str, content: &[u8]) -> Result<bool> {
+        self.save_snapshot_at(file_path, content, chrono::Utc::now().timestamp_millis())
+    }
 
-        // Scan backwards from the end if possible, or scan all and take last N
-        // sled scan is lexicographical. Timestamp is i64, so string sort works if padded...
-        // Wait, timestamp is standard i64 string. Variable length hurts sorting: 10 vs 2.
-        // Assuming timestamps are current epoch millis (13 digits), string sort is fine.
+    fn save_snapshot_at(&self, file_path: &str, content: &[u8], timestamp: i64) -> Result<bool> {
+        let _guard = self.lock()?;
+        let mut changes = Changes::default();
+        let saved = self.stage_snapshot(&mut changes, file_path, content, timestamp, None)?;
+        if saved {
+            self.apply(changes)?;
+        }
+        Ok(saved)
+    }
 
-        for item in self.db.scan_prefix(prefix.as_bytes()) {
-            let (key, _) = item?;
-            let key_str = String::from_utf8_lossy(&key);
-
-            // Format: __time_idx__:TIMESTAMP:PATH
-            let parts: Vec<&str> = key_str.splitn(3, ':').collect();
-            if parts.len() == 3 {
-                if let Ok(ts) = parts[1].parse::<i64>() {
-                    let path = parts[2].to_string();
-                    events.push((ts, path));
+    /// Records files saved together in one transaction, under a new
+    /// change-set id. A path listed twice is only saved once.
+    pub fn save_change_set(&self, files: &[(String, Vec<u8>)]) -> Result<ChangeSet> {
+        let _guard = self.lock()?;
+        let mut change_set = ChangeSet {
+            id: self.db.generate_id()?,
+            saved: vec![],
+            unchanged: vec![],
+        };
+        let timestamp = chrono::Utc::now().timestamp_millis();
+        let mut changes = Changes::default();
+        let mut seen = HashSet::new();
+        for (file_path, content) in files {
+            if !seen.insert(file_path) {
+                continue;
+            }
+            let id = Some(change_set.id);
+            if self.stage_snapshot(&mut changes, file_path, content, timestamp, id)? {
+                change_set.saved.push(file_path.clone());
+            } else {
+                change_set.unchanged.push(file_path.clone());
+            }
+        }
+        if !change_set.saved.is_empty() {
+            self.apply(changes)?;
+        }
+        Ok(change_set)
+    }
+
+    /// Adds a snapshot of `content` to `changes`, unless it equals the
+    /// previous snapshot of `file_path`. Returns whether one was added.
+    fn stage_snapshot(
+        &self,
+        changes: &mut Changes,
+        file_path: &str,
+        content: &[u8],
+        timestamp: i64,
+        change_set: Option<u64>,
+    ) -> Result<bool> {
+        let hash = content_hash(content);
+
+        // Blob of the previous snapshot, the base for a delta
+        let mut base = None;
+        let mut timestamp = timestamp;
+        if let Some((previous_ts, previous)) = self.latest_ref(file_path, i64::MAX)? {
+            if previous.hash == hash {
+                return Ok(false);
+            }
+            // Saves within the same millisecond, or after the clock went
+            // back, still land after the previous snapshot
+            timestamp = timestamp.max(previous_ts + 1);
+            base = Some(previous.hash);
+        }
+
+        let snapshot = SnapshotRef {
+            hash: hash.clone(),
+            size: content.len(),
+            change_set,
+        };
+        let refs = self.staged_refs(changes, &hash)?;
+        if refs == 0 {
+            let (blob, delta_base) = self.encode_blob(content, base.as_deref())?;
+            if let Some(base) = delta_base {
+                let base_refs = self.staged_refs(changes, base)?;
+                changes.refs.insert(base.to_string(), base_refs + 1);
+            }
+            changes.blobs.insert(hash.as_bytes(), blob);
+        }
+        changes.refs.insert(hash.clone(), refs + 1);
+        changes.snapshots.insert(
+            snapshot_key(file_path, timestamp),
+            serde_json::to_vec(&snapshot)?,
+        );
+        changes
+            .timeline
+            .insert(timeline_key(timestamp, file_path), &[]);
+        Ok(true)
+    }
+
+    /// Removes one snapshot, and its blob once no snapshot refers to it.
+    pub fn delete_snapshot(&self, file_path: &str, timestamp: i64) -> Result<()> {
+        let _guard = self.lock()?;
+        let key = snapshot_key(file_path, timestamp);
+        let Some(value) = self.snapshots.get(&key)? else {
+            return Ok(());
+        };
+
+        let mut changes = Changes::default();
+        changes.snapshots.remove(key);
+        changes.timeline.remove(timeline_key(timestamp, file_path));
+        // A released delta releases its base in turn
+        let mut release = Some(SnapshotRef::decode(&value)?.hash);
+        while let Some(hash) = release.take() {
+            let refs = self.refs(&hash)?;
+            if refs <= 1 {
+                if let Some(blob) = self.blobs.get(&hash)? {
+                    release = DeltaHeader::parse(&blob).map(|header| header.base);
                 }
+                changes.blob_refs.remove(hash.as_bytes());
+                changes.blobs.remove(hash.as_bytes());
+            } else {
+                changes
+                    .blob_refs
+                    .insert(hash.as_bytes(), &(refs - 1).to_be_bytes());
             }
         }
+        self.apply(changes)
+    }
 
-        // Sort descending (newest first)
-        events.sort_by(|a, b| b.0.cmp(&a.0));
-        events.truncate(limit);
+    /// Compresses `content` as a delta against the `base` blob when that is
+    /// smaller than a keyframe and the chain is short enough. Returns the blob
+    /// and, for a delta, the base it references.
+    fn encode_blob<'b>(
+        &self,
+        content: &[u8],
+        base: Option<&'b str>,
+    ) -> Result<(Vec<u8>, Option<&'b str>)> {
+        let full = zstd::encode_all(content, 0)?;
+        let Some(base) = base else {
+            return Ok((full, None));
+        };
+        let Some(base_blob) = self.blobs.get(base)? else {
+            return Ok((full, None));
+        };
+        let depth = blob_depth(&base_blob) + 1;
+        if depth >= KEYFRAME_INTERVAL {
+            return Ok((full, None));
+        }
+        let base_content = self.read_blob(base)?;
+        if base_content.len() < MIN_DELTA_BASE || base_content.starts_with(&ZSTD_DICT_MAGIC) {
+            return Ok((full, None));
+        }
 
-        Ok(events)
+        let mut delta = DELTA_TAG.to_vec();
+        delta.extend_from_slice(format!("{}:{}\n", depth, base).as_bytes());
+        delta.extend_from_slice(&compress_delta(content, &base_content)?);
+        if delta.len() < full.len() {
+            Ok((delta, Some(base)))
+        } else {
+            Ok((full, None))
+        }
     }
 
-    /// Recursively find the state of all tracked files at `target_timestamp`
-    /// and return a map of { file_path -> content }.
-    /// This allows reconstructing a "Ghost Branch" state.
-    pub fn get_checkpoint_state(&self, target_timestamp: i64) -> Result<Vec<(String, Vec<u8>)>> {
-        // 1. Identify all unique files that existed <= target_timestamp.
-        // We scan the global timeline.
-        let prefix = "__time_idx__:";
-        let mut unique_files = std::collections::HashSet::new();
+    /// Content of a blob, replaying its delta chain from the keyframe.
+    fn read_blob(&self, hash: &str) -> Result<Vec<u8>> {
+        let mut chain = Vec::new();
+        let mut next = hash.to_string();
+        let keyframe = loop {
+            let blob = self
+                .blobs
+                .get(&next)?
+                .ok_or_else(|| anyhow!("Missing blob {}", next))?;
+            match DeltaHeader::parse(&blob) {
+                Some(header) => {
+                    next = header.base.clone();
+                    chain.push((blob, header.data));
+                }
+                None => break blob,
+            }
+            if chain.len() > KEYFRAME_INTERVAL as usize {
+                return Err(anyhow!("Delta chain of blob {} too long", hash));
+            }
+        };
 
-        for item in self.db.scan_prefix(prefix.as_bytes()) {
-            let (key, _) = item?;
-            let key_str = String::from_utf8_lossy(&key);
-            let parts: Vec<&str> = key_str.splitn(3, ':').collect();
-            if parts.len() == 3 {
-                if let Ok(ts) = parts[1].parse::<i64>() {
-                    // Only consider files modified BEFORE or AT target
-                    if ts <= target_timestamp {
-                        unique_files.insert(parts[2].to_string());
-                    }
+        let mut content = zstd::decode_all(keyframe.as_ref())?;
+        for (blob, data) in chain.iter().rev() {
+            content = decompress_delta(&blob[*data..], &content)?;
+        }
+        Ok(content)
+    }
+
+    /// Counts snapshots and blobs and the bytes they take, compressed and not.
+    pub fn stats(&self) -> Result<StorageStats> {
+        let mut stats = StorageStats::default();
+        for value in self.snapshots.iter().values() {
+            stats.snapshots += 1;
+            stats.logical_bytes += SnapshotRef::decode(&value?)?.size as u64;
+        }
+        for blob in self.blobs.iter().values() {
+            let blob = blob?;
+            stats.stored_bytes += blob.len() as u64;
+            if blob.starts_with(DELTA_TAG) {
+                stats.delta_blobs += 1;
+            } else {
+                stats.full_blobs += 1;
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Prunes the snapshots `retention` no longer keeps at `now` and every
+    /// blob left without a reference, rebuilding the reference counts from
+    /// what remains. With `dry_run` only the report is computed.
+    pub fn gc(&self, retention: &RetentionConfig, now: i64, dry_run: bool) -> Result<GcReport> {
+        let _guard = self.lock()?;
+
+        let mut snapshots = Vec::new();
+        for item in self.snapshots.iter() {
+            let (key, value) = item?;
+            let (path, timestamp) =
+                parse_snapshot_key(&key).ok_or_else(|| anyhow!("Corrupt snapshot key"))?;
+            snapshots.push((path, timestamp, SnapshotRef::decode(&value)?.hash));
+        }
+        let mut sizes = HashMap::new();
+        let mut bases = HashMap::new();
+        for item in self.blobs.iter() {
+            let (hash, blob) = item?;
+            let hash = String::from_utf8_lossy(&hash).into_owned();
+            sizes.insert(hash.clone(), blob.len() as u64);
+            bases.insert(hash, DeltaHeader::parse(&blob).map(|header| header.base));
+        }
+
+        // Mark what every snapshot needs, then what the kept ones need
+        let mut all_refs = HashMap::new();
+        for (_, _, hash) in &snapshots {
+            add_ref(&mut all_refs, &bases, hash);
+        }
+        let listed: Vec<(&str, i64)> = snapshots
+            .iter()
+            .map(|(path, timestamp, _)| (path.as_str(), *timestamp))
+            .collect();
+        let mut keep = retention::keep(retention, &listed, now);
+        let mut refs = HashMap::new();
+        for ((_, _, hash), _) in snapshots.iter().zip(&keep).filter(|(_, kept)| **kept) {
+            add_ref(&mut refs, &bases, hash);
+        }
+
+        // Over the size cap, drop the oldest snapshots that are not the
+        // latest of their file
+        if let Some(max_size) = retention.max_size {
+            let mut size: u64 = refs.keys().filter_map(|hash| sizes.get(hash)).sum();
+            let mut candidates: Vec<usize> = (0..snapshots.len())
+                .filter(|&i| keep[i])
+                .filter(|&i| {
+                    snapshots.get(i + 1).map(|next| next.0 == snapshots[i].0) == Some(true)
+                })
+                .collect();
+            candidates.sort_by_key(|&i| snapshots[i].1);
+            for i in candidates {
+                if size <= max_size.0 {
+                    break;
+                }
+                keep[i] = false;
+                for hash in release_ref(&mut refs, &bases, &snapshots[i].2) {
+                    size -= sizes.get(&hash).copied().unwrap_or(0);
                 }
             }
         }
 
+        let mut report = GcReport {
+            stored_bytes: sizes.values().sum(),
+            ..GcReport::default()
+        };
+        let mut changes = Changes::default();
+        for ((path, timestamp, _), _) in snapshots.iter().zip(&keep).filter(|(_, kept)| !**kept) {
+            changes.snapshots.remove(snapshot_key(path, *timestamp));
+            changes.timeline.remove(timeline_key(*timestamp, path));
+            report.pruned.push((path.clone(), *timestamp));
+        }
+        for (hash, size) in &sizes {
+            if !refs.contains_key(hash) {
+                changes.blobs.remove(hash.as_bytes());
+                report.freed_blobs += 1;
+                report.freed_bytes += size;
+                if !all_refs.contains_key(hash) {
+                    report.orphaned_blobs += 1;
+                }
+            }
+        }
+        let mut counted = HashSet::new();
+        for item in self.blob_refs.iter() {
+            let (hash, value) = item?;
+            let hash = String::from_utf8_lossy(&hash).into_owned();
+            match refs.get(&hash) {
+                Some(count) if decode_count(Some(value)) == *count => {}
+                Some(count) => changes
+                    .blob_refs
+                    .insert(hash.as_bytes(), &count.to_be_bytes()),
+                None => changes.blob_refs.remove(hash.as_bytes()),
+            }
+            counted.insert(hash);
+        }
+        for (hash, count) in &refs {
+            if !counted.contains(hash) {
+                changes
+                    .blob_refs
+                    .insert(hash.as_bytes(), &count.to_be_bytes());
+            }
+        }
+
+        if !dry_run {
+            self.apply(changes)?;
+        }
+        Ok(report)
+    }
+
+    /// The newest snapshot of `file_path` taken at or before `timestamp`.
+    fn latest_ref(&self, file_path: &str, timestamp: i64) -> Result<Option<(i64, SnapshotRef)>> {
+        let range = snapshot_prefix(file_path)..=snapshot_key(file_path, timestamp);
+        match self.snapshots.range(range).next_back() {
+            Some(item) => {
+                let (key, value) = item?;
+                let (_, ts) = parse_snapshot_key(&key)
+                    .ok_or_else(|| anyhow!("Corrupt snapshot key for {}", file_path))?;
+                Ok(Some((ts, SnapshotRef::decode(&value)?)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// The state of `file_path` at `timestamp`: its newest snapshot taken at
+    /// or before it.
+    pub fn latest_before(&self, file_path: &str, timestamp: i64) -> Result<Option<SnapshotInfo>> {
+        Ok(self
+            .latest_ref(file_path, timestamp)?
+            .map(|(timestamp, snapshot)| SnapshotInfo {
+                timestamp,
+                size: snapshot.size,
+                change_set: snapshot.change_set,
+            }))
+    }
+
+    /// Snapshots of `file_path`, newest first.
+    pub fn get_history(&self, file_path: &str) -> Result<Vec<SnapshotInfo>> {
+        self.snapshots
+            .scan_prefix(snapshot_prefix(file_path))
+            .rev()
+            .map(|item| {
+                let (key, value) = item?;
+                let (_, timestamp) = parse_snapshot_key(&key)
+                    .ok_or_else(|| anyhow!("Corrupt snapshot key for {}", file_path))?;
+                let snapshot = SnapshotRef::decode(&value)?;
+                Ok(SnapshotInfo {
+                    timestamp,
+                    size: snapshot.size,
+                    change_set: snapshot.change_set,
+                })
+            })
+            .collect()
+    }
+
+    pub fn get_snapshot(&self, file_path: &str, timestamp: i64) -> Result<Option<Vec<u8>>> {
+        match self.snapshots.get(snapshot_key(file_path, timestamp))? {
+            Some(value) => Ok(Some(self.read_blob(&SnapshotRef::decode(&value)?.hash)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Retrieve a timeline of all file changes across the repository.
+    /// Returns a list of (timestamp, file_path) tuples, sorted newest first.
+    pub fn get_global_timeline(&self, limit: usize) -> Result<Vec<(i64, String)>> {
+        self.get_timeline_page(None, limit)
+    }
+
+    /// Up to `limit` timeline events older than `before`, newest first. Pass
+    /// the last event of a page to get the next one.
+    pub fn get_timeline_page(
+        &self,
+        before: Option<(i64, &str)>,
+        limit: usize,
+    ) -> Result<Vec<(i64, String)>> {
+        let events = match before {
+            Some((timestamp, file_path)) => {
+                self.timeline.range(..timeline_key(timestamp, file_path))
+            }
+            None => self.timeline.iter(),
+        };
+        events
+            .rev()
+            .take(limit)
+            .map(|item| {
+                let (key, _) = item?;
+                parse_timeline_key(&key).ok_or_else(|| anyhow!("Corrupt timeline key"))
+            })
+            .collect()
+    }
+
+    /// The content of every file with a snapshot at or before
+    /// `target_timestamp`, each at its latest such snapshot.
+    /// This allows reconstructing a "Ghost Branch" state.
+    pub fn get_checkpoint_state(&self, target_timestamp: i64) -> Result<Vec<(String, Vec<u8>)>> {
         let mut restored_files = Vec::new();
+        let mut next: Vec<u8> = Vec::new();
 
-        // 2. For each unique file, find its latest snapshot <= target_timestamp
-        for path in unique_files {
-            // Scan this file's history to find best match
-            // Optimization: We could use range query on "path:timestamp" if we knew the timestamp.
-            // Since we don't, we just get history and find max(t) <= target.
-
-            // Reuse get_history for simplicity inside this module
-            // BUT get_history parses all keys. Let's do a more direct scan if possible.
-            // Actually, get_history is fast enough for typical use cases per file.
-
-            // Manual scan for efficiency: Scan "path:" and stop when ts > target?
-            // Keys are "path:timestamp". Timestamp string sorting.
-            // 100 < 20. Need to be careful.
-            // Let's rely on get_history's logic for now as it's robust.
-
-            let history = self.get_history(&path)?;
-            // History is sorted desc. Find first (newest) that is <= target.
-            if let Some(snapshot) = history.iter().find(|s| s.timestamp <= target_timestamp) {
-                if let Ok(Some(content)) = self.get_snapshot(&path, snapshot.timestamp) {
-                    restored_files.push((path, content));
+        // One seek per file: `path \x01` sorts right after its last snapshot
+        while let Some(item) = self.snapshots.range(next.as_slice()..).next() {
+            let (key, _) = item?;
+            let (path, _) =
+                parse_snapshot_key(&key).ok_or_else(|| anyhow!("Corrupt snapshot key"))?;
+            if let Some((_, snapshot)) = self.latest_ref(&path, target_timestamp)? {
+                if let Ok(content) = self.read_blob(&snapshot.hash) {
+                    restored_files.push((path.clone(), content));
                 }
             }
+            next = path.into_bytes();
+            next.push(1);
         }
 
         Ok(restored_files)
@@ -163,3 +861,311 @@ impl ChronosStore {
 pub fn init_db() {
     // Placeholder
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store() -> ChronosStore {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        ChronosStore::from_db(db).unwrap()
+    }
+
+    #[test]
+    fn test_dedup() {
+        let store = temp_store();
+        assert!(store.save_snapshot("a.txt", b"one").unwrap());
+        assert!(!store.save_snapshot("a.txt", b"one").unwrap());
+        assert!(store.save_snapshot("b.txt", b"one").unwrap());
+        assert!(store.save_snapshot("a.txt", b"two").unwrap());
+        assert!(store.save_snapshot("a.txt", b"one").unwrap());
+        assert_eq!(store.blobs.len(), 2);
+
+        let history = store.get_history("a.txt").unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].size, 3);
+        assert_eq!(
+            store.get_snapshot("a.txt", history[1].timestamp).unwrap(),
+            Some(b"two".to_vec())
+        );
+        assert_eq!(store.refs(&content_hash(b"one")).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_delete_releases_blob() {
+        let store = temp_store();
+        store.save_snapshot("a.txt", b"one").unwrap();
+        store.save_snapshot("b.txt", b"one").unwrap();
+        let a = store.get_history("a.txt").unwrap()[0].timestamp;
+        let b = store.get_history("b.txt").unwrap()[0].timestamp;
+
+        store.delete_snapshot("a.txt", a).unwrap();
+        assert_eq!(store.blobs.len(), 1);
+        assert_eq!(
+            store.get_snapshot("b.txt", b).unwrap(),
+            Some(b"one".to_vec())
+        );
+        store.delete_snapshot("b.txt", b).unwrap();
+        assert_eq!(store.blobs.len(), 0);
+        assert!(store.get_global_timeline(10).unwrap().is_empty());
+    }
+
+    /// A few KiB of text that differs from one version to the next.
+    fn version(n: usize) -> Vec<u8> {
+        (0..200)
+            .map(|i| {
+                format!(
+                    "line {} of the file, revision {}\n",
+                    i,
+                    if i % 7 == 0 { n } else { 0 }
+                )
+            })
+            .collect::<String>()
+            .into_bytes()
+    }
+
+    #[test]
+    fn test_delta_chain() {
+        let store = temp_store();
+        let versions = 2 * KEYFRAME_INTERVAL as usize + 2;
+        for n in 0..versions {
+            assert!(store.save_snapshot("big.txt", &version(n)).unwrap());
+        }
+
+        let stats = store.stats().unwrap();
+        assert_eq!(stats.snapshots, versions);
+        assert_eq!(stats.full_blobs, 3);
+        assert_eq!(stats.delta_blobs, versions - 3);
+        assert!(stats.ratio() > 10.0, "ratio {}", stats.ratio());
+
+        let history = store.get_history("big.txt").unwrap();
+        for (n, info) in history.iter().rev().enumerate() {
+            assert_eq!(
+                store.get_snapshot("big.txt", info.timestamp).unwrap(),
+                Some(version(n))
+            );
+        }
+    }
+
+    #[test]
+    fn test_delete_keeps_delta_bases() {
+        let store = temp_store();
+        for n in 0..3 {
+            store.save_snapshot("big.txt", &version(n)).unwrap();
+        }
+        let history = store.get_history("big.txt").unwrap();
+        let (newest, oldest) = (history[0].timestamp, history[2].timestamp);
+
+        // The keyframe outlives its snapshot while deltas depend on it
+        store.delete_snapshot("big.txt", oldest).unwrap();
+        assert_eq!(store.blobs.len(), 3);
+        assert_eq!(
+            store.get_snapshot("big.txt", newest).unwrap(),
+            Some(version(2))
+        );
+        for info in &history[..2] {
+            store.delete_snapshot("big.txt", info.timestamp).unwrap();
+        }
+        assert_eq!(store.blobs.len(), 0);
+        assert_eq!(store.blob_refs.len(), 0);
+    }
+
+    #[test]
+    fn test_range_queries() {
+        let store = temp_store();
+        // Saved oldest first, decimal strings would sort them -5, 10, 100, 9
+        for (ts, content) in [(-5, "a"), (9, "b"), (10, "c"), (100, "d")] {
+            store
+                .save_snapshot_at("C:/src/a:b.rs", content.as_bytes(), ts)
+                .unwrap();
+        }
+        store.save_snapshot_at("C:/src/a", b"other", 50).unwrap();
+
+        let history: Vec<i64> = store
+            .get_history("C:/src/a:b.rs")
+            .unwrap()
+            .iter()
+            .map(|s| s.timestamp)
+            .collect();
+        assert_eq!(history, vec![100, 10, 9, -5]);
+        assert_eq!(store.get_history("C:/src/a").unwrap().len(), 1);
+
+        let at = |ts| {
+            store
+                .latest_before("C:/src/a:b.rs", ts)
+                .unwrap()
+                .map(|s| s.timestamp)
+        };
+        assert_eq!(at(50), Some(10));
+        assert_eq!(at(10), Some(10));
+        assert_eq!(at(-6), None);
+
+        let page = store.get_timeline_page(None, 2).unwrap();
+        assert_eq!(page[0], (100, "C:/src/a:b.rs".to_string()));
+        assert_eq!(page[1], (50, "C:/src/a".to_string()));
+        let (ts, path) = &page[1];
+        let rest = store.get_timeline_page(Some((*ts, path)), 10).unwrap();
+        assert_eq!(
+            rest.iter().map(|e| e.0).collect::<Vec<_>>(),
+            vec![10, 9, -5]
+        );
+
+        let state = store.get_checkpoint_state(20).unwrap();
+        assert_eq!(state, vec![("C:/src/a:b.rs".to_string(), b"c".to_vec())]);
+    }
+
+    #[test]
+    fn test_gc() {
+        let store = temp_store();
+        let hour = 60 * 60 * 1000;
+        let now = 1_000 * 24 * hour;
+        for (n, age) in [(0, 60 * 24), (1, 30 * 24), (2, 48), (3, 47), (4, 1)] {
+            store
+                .save_snapshot_at("big.txt", &version(n), now - age * hour)
+                .unwrap();
+        }
+        store.save_snapshot_at("old.txt", b"old", 0).unwrap();
+        // Left behind without a snapshot, e.g. by an interrupted write
+        store
+            .blobs
+            .insert("f00d", zstd::encode_all(&b"lost"[..], 0).unwrap())
+            .unwrap();
+        store.blob_refs.insert("f00d", &1u64.to_be_bytes()).unwrap();
+
+        let retention = RetentionConfig::default();
+        let dry = store.gc(&retention, now, true).unwrap();
+        let pruned: Vec<i64> = dry.pruned.iter().map(|(_, ts)| (now - ts) / hour).collect();
+        assert_eq!(pruned, vec![60 * 24, 30 * 24]);
+        assert_eq!(dry.orphaned_blobs, 1);
+        assert_eq!(store.stats().unwrap().snapshots, 6);
+
+        let report = store.gc(&retention, now, false).unwrap();
+        assert_eq!(report.pruned, dry.pruned);
+        assert_eq!(report.freed_blobs, dry.freed_blobs);
+        assert!(!store.blobs.contains_key("f00d").unwrap());
+        let history = store.get_history("big.txt").unwrap();
+        assert_eq!(history.len(), 3);
+        // The survivors still decode, whatever their delta bases were
+        for (info, n) in history.iter().zip([4, 3, 2]) {
+            assert_eq!(
+                store.get_snapshot("big.txt", info.timestamp).unwrap(),
+                Some(version(n))
+            );
+        }
+        assert_eq!(store.get_history("old.txt").unwrap().len(), 1);
+
+        // Deleting the rest one by one leaves nothing, so the counts are exact
+        for (path, ts) in [("old.txt", 0)]
+            .into_iter()
+            .chain(history.iter().map(|i| ("big.txt", i.timestamp)))
+        {
+            store.delete_snapshot(path, ts).unwrap();
+        }
+        assert_eq!(store.blobs.len(), 0);
+        assert_eq!(store.blob_refs.len(), 0);
+    }
+
+    #[test]
+    fn test_gc_max_size() {
+        let store = temp_store();
+        for n in 0..5 {
+            let content: Vec<u8> = (0..4096u32).map(|i| (i * 7 + n * 13) as u8).collect();
+            store
+                .save_snapshot_at("noise.bin", &content, n as i64)
+                .unwrap();
+        }
+        let retention = RetentionConfig {
+            max_size: Some(crate::config::ByteSize(1)),
+            ..RetentionConfig::default()
+        };
+        let report = store.gc(&retention, 10, false).unwrap();
+        assert_eq!(report.pruned.len(), 4);
+        let history = store.get_history("noise.bin").unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].timestamp, 4);
+        assert!(store.get_snapshot("noise.bin", 4).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_change_set() {
+        let store = temp_store();
+        store.save_snapshot("a.txt", b"same").unwrap();
+        let files = vec![
+            ("a.txt".to_string(), b"same".to_vec()),
+            ("b.txt".to_string(), b"shared".to_vec()),
+            ("c.txt".to_string(), b"shared".to_vec()),
+            ("b.txt".to_string(), b"listed twice".to_vec()),
+        ];
+        let first = store.save_change_set(&files).unwrap();
+        assert_eq!(first.saved, vec!["b.txt", "c.txt"]);
+        assert_eq!(first.unchanged, vec!["a.txt"]);
+        // Both snapshots of the new blob were counted within the batch
+        assert_eq!(store.refs(&content_hash(b"shared")).unwrap(), 2);
+        assert_eq!(store.blobs.len(), 2);
+
+        let b = &store.get_history("b.txt").unwrap()[0];
+        let c = &store.get_history("c.txt").unwrap()[0];
+        assert_eq!(b.change_set, Some(first.id));
+        assert_eq!(c.change_set, Some(first.id));
+        assert_eq!(store.get_history("a.txt").unwrap()[0].change_set, None);
+
+        let second = store
+            .save_change_set(&[("b.txt".to_string(), b"edited".to_vec())])
+            .unwrap();
+        assert_ne!(second.id, first.id);
+        assert!(store.save_change_set(&[]).unwrap().saved.is_empty());
+    }
+
+    #[test]
+    fn test_migrate_v1() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let new = b"new content";
+        let hash = content_hash(new);
+        let v1 = |key: &str, value: Vec<u8>| db.insert(key, value).unwrap();
+        // Inline content, written before blobs existed
+        v1(
+            "src/a:b.rs:1700000000000",
+            zstd::encode_all(&b"old"[..], 0).unwrap(),
+        );
+        v1(
+            "src/a:b.rs:1700000000100",
+            format!("{{\"hash\":\"{}\",\"size\":{}}}", hash, new.len()).into_bytes(),
+        );
+        v1(
+            &format!("__blob__:{}", hash),
+            zstd::encode_all(&new[..], 0).unwrap(),
+        );
+        v1(
+            &format!("__blob_refs__:{}", hash),
+            7u64.to_be_bytes().to_vec(),
+        );
+        v1("__time_idx__:1700000000000:src/a:b.rs", vec![]);
+        v1("__time_idx__:1700000000100:src/a:b.rs", vec![]);
+        v1("old.rs:999", zstd::encode_all(&b"older"[..], 0).unwrap());
+
+        let store = ChronosStore::from_db(db).unwrap();
+        assert_eq!(store.db.len(), 1);
+        assert_eq!(store.refs(&hash).unwrap(), 1);
+        let history = store.get_history("src/a:b.rs").unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            store
+                .get_snapshot("src/a:b.rs", history[0].timestamp)
+                .unwrap(),
+            Some(new.to_vec())
+        );
+        assert_eq!(
+            store
+                .get_snapshot("src/a:b.rs", history[1].timestamp)
+                .unwrap(),
+            Some(b"old".to_vec())
+        );
+        assert_eq!(
+            store.get_global_timeline(10).unwrap().last(),
+            Some(&(999, "old.rs".to_string()))
+        );
+        // Already at the current schema, nothing to do
+        store.migrate().unwrap();
+        assert_eq!(store.stats().unwrap().snapshots, 3);
+    }
+}
@@ -1,9 +1,63 @@
 use crate::chronos::storage::ChronosStore;
 use crate::config::Config; // Import Config
+use crate::core::ignore_rules::{IgnoreRules, IGNORE_FILES};
 use notify::{Config as NotifyConfig, EventKind, RecommendedWatcher, RecursiveMode, Watcher}; // Rename notify::Config
+use std::collections::BTreeSet;
 use std::fs;
-use std::path::Path;
-use std::sync::mpsc::channel;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// A burst that never goes quiet is still flushed after this many windows.
+const MAX_WAIT_WINDOWS: u32 = 10;
+
+/// Collects changed paths until the tree has been quiet for a window, so one
+/// save, or a formatter rewriting many files, gives a single change set with
+/// one snapshot per file however many events it raised.
+struct Debouncer {
+    quiet: Duration,
+    max_wait: Duration,
+    pending: BTreeSet<PathBuf>,
+    first: Instant,
+    last: Instant,
+}
+
+impl Debouncer {
+    fn new(quiet: Duration) -> Self {
+        let now = Instant::now();
+        Self {
+            quiet,
+            max_wait: quiet * MAX_WAIT_WINDOWS,
+            pending: BTreeSet::new(),
+            first: now,
+            last: now,
+        }
+    }
+
+    fn add(&mut self, path: PathBuf, now: Instant) {
+        if self.pending.is_empty() {
+            self.first = now;
+        }
+        self.pending.insert(path);
+        self.last = now;
+    }
+
+    fn deadline(&self) -> Option<Instant> {
+        if self.pending.is_empty() {
+            None
+        } else {
+            Some((self.last + self.quiet).min(self.first + self.max_wait))
+        }
+    }
+
+    /// The pending paths, once their deadline has passed.
+    fn take_due(&mut self, now: Instant) -> Option<BTreeSet<PathBuf>> {
+        match self.deadline() {
+            Some(deadline) if now >= deadline => Some(std::mem::take(&mut self.pending)),
+            _ => None,
+        }
+    }
+}
 
 pub fn watch<P: AsRef<Path>>(path: P, config: &Config, store: ChronosStore) -> notify::Result<()> {
     if !config.chronos.enabled {
@@ -11,6 +65,11 @@ pub fn watch<P: AsRef<Path>>(path: P, config: &Config, store: ChronosStore) -> n
         return Ok(());
     }
     let (tx, rx) = channel();
+    let mut ignore_rules = IgnoreRules::load(path.as_ref());
+    for error in ignore_rules.errors() {
+        println!("Ignore rules: {}", error);
+    }
+    let mut debouncer = Debouncer::new(Duration::from_millis(config.chronos.debounce_ms));
 
     // Automatically select the best implementation for your platform.
     let mut watcher = RecommendedWatcher::new(tx, NotifyConfig::default())?;
@@ -19,38 +78,109 @@ pub fn watch<P: AsRef<Path>>(path: P, config: &Config, store: ChronosStore) -> n
     // below will be monitored for changes.
     watcher.watch(path.as_ref(), RecursiveMode::Recursive)?;
 
-    for res in rx {
-        match res {
-            Ok(event) => {
-                // Filter out .git directory and other noise
-                if let Some(path) = event.paths.get(0) {
-                    if path.to_string_lossy().contains(".git")
-                        || path.to_string_lossy().contains("target")
-                    {
-                        continue;
-                    }
+    loop {
+        let received = match debouncer.deadline() {
+            Some(deadline) => {
+                match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                    Ok(res) => Some(res),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+            None => match rx.recv() {
+                Ok(res) => Some(res),
+                Err(_) => break,
+            },
+        };
 
-                    // Only act on Modify or Create events
-                    match event.kind {
-                        EventKind::Modify(_) | EventKind::Create(_) => {
-                            println!("Change detected in: {:?}", path);
-                            if let Ok(content) = fs::read(path) {
-                                if let Err(e) =
-                                    store.save_snapshot(&path.to_string_lossy(), &content)
-                                {
-                                    println!("Failed to save snapshot: {}", e);
-                                } else {
-                                    println!("Snapshot saved for {:?}", path);
-                                }
-                            }
-                        }
-                        _ => {}
+        match received {
+            Some(Ok(event)) => {
+                // Only act on Modify or Create events
+                let changed = matches!(event.kind, EventKind::Modify(_) | EventKind::Create(_));
+                for path in event.paths {
+                    let is_ignore_file = path
+                        .file_name()
+                        .map(|name| IGNORE_FILES.iter().any(|f| name == *f))
+                        .unwrap_or(false);
+                    if is_ignore_file {
+                        ignore_rules.reload();
+                    }
+                    // Filter out .git, ignored files and other noise
+                    if changed && !ignore_rules.is_ignored(&path, path.is_dir()) {
+                        debouncer.add(path, Instant::now());
                     }
                 }
             }
-            Err(e) => println!("watch error: {:?}", e),
+            Some(Err(e)) => println!("watch error: {:?}", e),
+            None => {}
+        }
+
+        if let Some(paths) = debouncer.take_due(Instant::now()) {
+            save_change_set(&store, paths);
         }
     }
 
+    // Whatever was still settling when the watcher stopped
+    if !debouncer.pending.is_empty() {
+        save_change_set(&store, std::mem::take(&mut debouncer.pending));
+    }
     Ok(())
 }
+
+fn save_change_set(store: &ChronosStore, paths: BTreeSet<PathBuf>) {
+    // Deleted since the event, or a directory
+    let files: Vec<(String, Vec<u8>)> = paths
+        .iter()
+        .filter(|path| path.is_file())
+        .filter_map(|path| Some((path.to_string_lossy().into_owned(), fs::read(path).ok()?)))
+        .collect();
+    if files.is_empty() {
+        return;
+    }
+    match store.save_change_set(&files) {
+        Ok(change_set) if change_set.saved.is_empty() => {
+            println!("Unchanged, snapshots skipped: {:?}", change_set.unchanged)
+        }
+        Ok(change_set) => println!(
+            "Change set #{} saved: {:?} ({} unchanged)",
+            change_set.id,
+            change_set.saved,
+            change_set.unchanged.len()
+        ),
+        Err(e) => println!("Failed to save snapshots: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debounce() {
+        let quiet = Duration::from_millis(100);
+        let mut debouncer = Debouncer::new(quiet);
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        assert!(debouncer.take_due(at(0)).is_none());
+
+        // Repeated events on one file coalesce, the window restarts each time
+        debouncer.add(PathBuf::from("a.rs"), at(0));
+        debouncer.add(PathBuf::from("b.rs"), at(50));
+        debouncer.add(PathBuf::from("a.rs"), at(120));
+        assert!(debouncer.take_due(at(200)).is_none());
+        let batch = debouncer.take_due(at(220)).unwrap();
+        assert_eq!(batch.len(), 2);
+        assert!(debouncer.deadline().is_none());
+
+        // A burst that never settles is cut at the max wait
+        for ms in (300..2000).step_by(50) {
+            debouncer.add(PathBuf::from(format!("f{}.rs", ms)), at(ms));
+            if let Some(batch) = debouncer.take_due(at(ms)) {
+                assert_eq!(ms, 1300);
+                assert_eq!(batch.len(), 21);
+                return;
+            }
+        }
+        panic!("burst was never flushed");
+    }
+}
@@ -0,0 +1,64 @@
+use crate::cli::EXIT_CLEAN;
+use crate::config::Config;
+use crate::core::GitRepository;
+use crate::sentinel::baseline::{Baseline, BASELINE_FILE};
+use crate::sentinel::Sentinel;
+use anyhow::Result;
+use clap::Args;
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+
+#[derive(Args, Debug)]
+pub struct BaselineArgs {
+    /// Also drop entries whose findings no longer occur
+    #[arg(long)]
+    pub prune: bool,
+}
+
+/// Scans every tracked (and changed) file and records all current findings
+/// in the baseline, keeping existing entries unless `--prune` is given.
+pub fn run(config: &Config, args: &BaselineArgs) -> Result<i32> {
+    let sentinel = Sentinel::new(config);
+    for error in sentinel.config_errors() {
+        eprintln!("sgit: {}", error);
+    }
+
+    let repo = GitRepository::open(".")?;
+    let root = repo.workdir().unwrap_or(Path::new(".")).to_path_buf();
+    let mut paths: BTreeSet<String> = repo.tracked_paths()?.into_iter().collect();
+    paths.extend(repo.changed_paths()?);
+
+    let mut baseline = Baseline::load(BASELINE_FILE)?;
+    let mut seen = HashSet::new();
+    let mut added = 0;
+
+    for path in paths {
+        let abs = root.join(&path);
+        if !abs.is_file() || path == BASELINE_FILE {
+            continue;
+        }
+        for finding in sentinel.scan_file_as(&abs, &path)? {
+            seen.insert(finding.fingerprint.clone());
+            if baseline.insert(&finding) {
+                added += 1;
+            }
+        }
+    }
+
+    let pruned = if args.prune {
+        baseline.retain(&seen)
+    } else {
+        0
+    };
+    baseline.sort();
+    baseline.save(BASELINE_FILE)?;
+
+    println!(
+        "{}: {} added, {} pruned, {} total",
+        BASELINE_FILE,
+        added,
+        pruned,
+        baseline.entries.len()
+    );
+    Ok(EXIT_CLEAN)
+}
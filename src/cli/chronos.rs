@@ -0,0 +1,62 @@
+use crate::chronos::storage::ChronosStore;
+use crate::cli::EXIT_CLEAN;
+use crate::config::{ByteSize, Config};
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+
+#[derive(Args, Debug)]
+pub struct ChronosArgs {
+    #[command(subcommand)]
+    pub command: ChronosCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ChronosCommand {
+    /// Prune snapshots outside the [chronos.retention] policy and unreferenced blobs
+    Gc {
+        /// Report what would be removed without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+        /// List every pruned snapshot
+        #[arg(short, long)]
+        verbose: bool,
+    },
+}
+
+pub fn run(config: &Config, args: &ChronosArgs) -> Result<i32> {
+    match &args.command {
+        ChronosCommand::Gc { dry_run, verbose } => gc(config, *dry_run, *verbose),
+    }
+}
+
+fn gc(config: &Config, dry_run: bool, verbose: bool) -> Result<i32> {
+    let path = config.chronos.store_path();
+    // sled locks the database, so this fails while the TUI is open
+    let store = ChronosStore::open(path).with_context(|| {
+        format!(
+            "Cannot open the Chronos store at {} (is sgit running?)",
+            path.display()
+        )
+    })?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let report = store.gc(&config.chronos.retention, now, dry_run)?;
+
+    if verbose {
+        for (file, timestamp) in &report.pruned {
+            let date = chrono::DateTime::from_timestamp_millis(*timestamp)
+                .map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_else(|| timestamp.to_string());
+            println!("  {}  {}", date, file);
+        }
+    }
+    println!(
+        "{}{} snapshots pruned, {} blobs freed ({} orphaned), {} of {} reclaimed",
+        if dry_run { "Dry run: " } else { "" },
+        report.pruned.len(),
+        report.freed_blobs,
+        report.orphaned_blobs,
+        ByteSize(report.freed_bytes),
+        ByteSize(report.stored_bytes)
+    );
+    Ok(EXIT_CLEAN)
+}
@@ -0,0 +1,299 @@
+use crate::cli::scan::{self, OutputFormat};
+use crate::cli::{EXIT_BLOCKED, EXIT_CLEAN};
+use crate::config::Config;
+use crate::core::ignore_rules::IgnoreRules;
+use crate::core::GitRepository;
+use crate::sentinel::history;
+use crate::sentinel::report::ScanReport;
+use crate::sentinel::{Sentinel, COMMIT_MESSAGE};
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand, ValueEnum};
+use git2::Oid;
+use std::fs;
+use std::io::{self, BufRead};
+use std::path::Path;
+
+/// First line after the shebang of every hook we write.
+const MARKER: &str = "# sgit-managed-hook";
+
+/// Suffix given to a pre-existing hook so our script can still run it.
+const CHAINED_SUFFIX: &str = ".sgit-chained";
+
+/// Follows the comment prefix on the line `git commit -v` puts above the diff.
+const SCISSORS: &str = "------------------------ >8 ------------------------";
+
+/// Prefixes git chooses from when `core.commentChar` is `auto`.
+const AUTO_COMMENT_CHARS: &[&str] = &["#", ";", "@", "!", "$", "%", "^", "&", "|", ":"];
+
+#[derive(Args, Debug)]
+pub struct HookArgs {
+    #[command(subcommand)]
+    pub command: HookCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HookCommand {
+    /// Install the pre-commit, commit-msg and pre-push hooks, chaining any existing ones
+    Install,
+    /// Remove the sgit hooks and restore the chained ones
+    Uninstall,
+    /// Entry point called by the installed hook scripts
+    Run {
+        #[arg(value_enum)]
+        hook: HookKind,
+        /// Arguments git passed to the hook
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HookKind {
+    PreCommit,
+    CommitMsg,
+    PrePush,
+}
+
+impl HookKind {
+    const ALL: [HookKind; 3] = [HookKind::PreCommit, HookKind::CommitMsg, HookKind::PrePush];
+
+    fn file_name(self) -> &'static str {
+        match self {
+            HookKind::PreCommit => "pre-commit",
+            HookKind::CommitMsg => "commit-msg",
+            HookKind::PrePush => "pre-push",
+        }
+    }
+
+    /// Hooks that receive data on stdin must buffer it so both the chained
+    /// hook and sgit can read it.
+    fn reads_stdin(self) -> bool {
+        matches!(self, HookKind::PrePush)
+    }
+}
+
+pub fn run(config: &Config, args: &HookArgs) -> Result<i32> {
+    let repo = GitRepository::open(".")?;
+    match &args.command {
+        HookCommand::Install => install(&repo),
+        HookCommand::Uninstall => uninstall(&repo),
+        HookCommand::Run { hook, args } => run_hook(config, &repo, *hook, args),
+    }
+}
+
+fn install(repo: &GitRepository) -> Result<i32> {
+    let dir = repo.hooks_dir()?;
+    fs::create_dir_all(&dir)?;
+    let exe = std::env::current_exe().context("Cannot locate the sgit executable")?;
+
+    for kind in HookKind::ALL {
+        let hook_path = dir.join(kind.file_name());
+        let chained_path = dir.join(format!("{}{}", kind.file_name(), CHAINED_SUFFIX));
+
+        if hook_path.exists() && !is_managed(&hook_path) {
+            if chained_path.exists() {
+                return Err(anyhow::anyhow!(
+                    "Both {} and {} exist; resolve them manually",
+                    hook_path.display(),
+                    chained_path.display()
+                ));
+            }
+            fs::rename(&hook_path, &chained_path)?;
+            println!(
+                "Existing {} hook kept as {}",
+                kind.file_name(),
+                chained_path.display()
+            );
+        }
+
+        fs::write(&hook_path, hook_script(kind, &exe))?;
+        make_executable(&hook_path)?;
+        println!("Installed {}", hook_path.display());
+    }
+    Ok(EXIT_CLEAN)
+}
+
+fn uninstall(repo: &GitRepository) -> Result<i32> {
+    let dir = repo.hooks_dir()?;
+    for kind in HookKind::ALL {
+        let hook_path = dir.join(kind.file_name());
+        let chained_path = dir.join(format!("{}{}", kind.file_name(), CHAINED_SUFFIX));
+
+        if !hook_path.exists() || !is_managed(&hook_path) {
+            continue;
+        }
+        fs::remove_file(&hook_path)?;
+        if chained_path.exists() {
+            fs::rename(&chained_path, &hook_path)?;
+            println!("Restored original {} hook", kind.file_name());
+        } else {
+            println!("Removed {}", hook_path.display());
+        }
+    }
+    Ok(EXIT_CLEAN)
+}
+
+fn run_hook(config: &Config, repo: &GitRepository, kind: HookKind, args: &[String]) -> Result<i32> {
+    let sentinel = Sentinel::new(config);
+    for error in sentinel.config_errors() {
+        eprintln!("sgit: {}", error);
+    }
+    let report = match kind {
+        HookKind::PreCommit => scan::scan_staged(&sentinel, repo)?,
+        HookKind::CommitMsg => scan_commit_message(&sentinel, repo, args)?,
+        HookKind::PrePush => scan_push(&sentinel, repo, io::stdin().lock())?,
+    };
+
+    if !report.is_blocking(sentinel.fail_on()) {
+        return Ok(EXIT_CLEAN);
+    }
+
+    // Git shows hook stderr to the user; stdout may be swallowed by GUIs
+    eprint!(
+        "{}",
+        scan::render(&report, OutputFormat::Text, sentinel.fail_on())
+    );
+    let action = match kind {
+        HookKind::PreCommit | HookKind::CommitMsg => "commit",
+        HookKind::PrePush => "push",
+    };
+    eprintln!(
+        "sgit: {} blocked by Sentinel (findings at or above '{}'). Use --no-verify to bypass.",
+        action,
+        sentinel.fail_on()
+    );
+    Ok(EXIT_BLOCKED)
+}
+
+/// Scans the message file git passes to commit-msg, without the comment
+/// lines and the scissors section (the `git commit -v` diff) git strips itself.
+fn scan_commit_message(
+    sentinel: &Sentinel,
+    repo: &GitRepository,
+    args: &[String],
+) -> Result<ScanReport> {
+    let path = args
+        .first()
+        .context("commit-msg hook called without a message file")?;
+    let raw = fs::read_to_string(path)?;
+    let comment = match repo.comment_prefix()?.as_str() {
+        "auto" => auto_comment_prefix(&raw).to_string(),
+        prefix => prefix.to_string(),
+    };
+    let scissors = format!("{} {}", comment, SCISSORS);
+    let message: Vec<&str> = raw
+        .lines()
+        .take_while(|l| *l != scissors)
+        .filter(|l| !l.starts_with(comment.as_str()))
+        .collect();
+    let mut report = ScanReport::default();
+    report.add(
+        COMMIT_MESSAGE,
+        sentinel.scan_text(COMMIT_MESSAGE, &message.join("\n")),
+    );
+    Ok(report)
+}
+
+/// The prefix git picked for `core.commentChar = auto`: the one on the
+/// scissors line, else the one the template comments at the end start with.
+fn auto_comment_prefix(raw: &str) -> &'static str {
+    let scissors = AUTO_COMMENT_CHARS
+        .iter()
+        .find(|c| raw.lines().any(|l| l == format!("{} {}", c, SCISSORS)));
+    let last = raw.lines().rev().find(|l| !l.trim().is_empty());
+    scissors
+        .or_else(|| last.and_then(|l| AUTO_COMMENT_CHARS.iter().find(|c| l.starts_with(**c))))
+        .copied()
+        .unwrap_or("#")
+}
+
+/// Scans the lines added by the commits described by pre-push stdin lines:
+/// `<local ref> <local sha> <remote ref> <remote sha>`, and the name of the
+/// remote ref, which may be a new branch or tag.
+fn scan_push<R: BufRead>(
+    sentinel: &Sentinel,
+    repo: &GitRepository,
+    input: R,
+) -> Result<ScanReport> {
+    let sgitignore = IgnoreRules::sgitignore_only(repo.workdir().unwrap_or(Path::new(".")));
+    let mut report = ScanReport::default();
+    for line in input.lines() {
+        let line = line?;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 4 {
+            continue;
+        }
+
+        let local = Oid::from_str(fields[1])?;
+        if local.is_zero() {
+            continue; // Branch deletion, nothing is uploaded
+        }
+        let remote = Oid::from_str(fields[3])?;
+        let remote = if remote.is_zero() { None } else { Some(remote) };
+
+        let name = fields[2];
+        report.add(name, sentinel.scan_text(name, name));
+
+        // Every pushed commit, not just the tip: a secret added and removed
+        // again within the push is still uploaded
+        for oid in repo.commits_in_push(local, remote)? {
+            report.add_commit(history::scan_commit(sentinel, repo, &sgitignore, oid)?);
+        }
+    }
+    Ok(report)
+}
+
+fn is_managed(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .map(|s| s.lines().take(2).any(|l| l == MARKER))
+        .unwrap_or(false)
+}
+
+/// `path` as a single-quoted shell word, so `"`, `$` and backticks in it are
+/// taken literally. A `'` is written as `'\''`.
+fn shell_quote(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', "'\\''"))
+}
+
+fn hook_script(kind: HookKind, exe: &Path) -> String {
+    let name = kind.file_name();
+    let mut script = format!(
+        "#!/bin/sh\n\
+         {MARKER}\n\
+         # Installed by `sgit hook install`, remove with `sgit hook uninstall`.\n\
+         SGIT={exe}\n\
+         [ -x \"$SGIT\" ] || SGIT=sgit\n\
+         CHAINED=\"$(dirname \"$0\")/{name}{CHAINED_SUFFIX}\"\n",
+        exe = shell_quote(exe),
+    );
+
+    if kind.reads_stdin() {
+        script.push_str(&format!(
+            "INPUT=$(cat)\n\
+             if [ -x \"$CHAINED\" ]; then\n\
+             \x20   printf '%s\\n' \"$INPUT\" | \"$CHAINED\" \"$@\" || exit $?\n\
+             fi\n\
+             printf '%s\\n' \"$INPUT\" | \"$SGIT\" hook run {name} -- \"$@\"\n"
+        ));
+    } else {
+        script.push_str(&format!(
+            "if [ -x \"$CHAINED\" ]; then\n\
+             \x20   \"$CHAINED\" \"$@\" || exit $?\n\
+             fi\n\
+             exec \"$SGIT\" hook run {name} -- \"$@\"\n"
+        ));
+    }
+    script
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,10 @@
+// CLI: Headless commands (no TUI)
+pub mod baseline;
+pub mod chronos;
+pub mod hook;
+pub mod scan;
+
+/// Process exit codes shared by headless commands.
+pub const EXIT_CLEAN: i32 = 0;
+pub const EXIT_BLOCKED: i32 = 1;
+pub const EXIT_ERROR: i32 = 2;
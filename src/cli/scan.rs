@@ -0,0 +1,193 @@
+use crate::cli::{EXIT_BLOCKED, EXIT_CLEAN};
+use crate::config::Config;
+use crate::core::ignore_rules::IgnoreRules;
+use crate::core::GitRepository;
+use crate::sentinel::history;
+use crate::sentinel::report::{self, ScanReport};
+use crate::sentinel::{Sentinel, Severity};
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use git2::Oid;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Sarif,
+}
+
+#[derive(Args, Debug)]
+pub struct ScanArgs {
+    /// Files or directories to scan. Defaults to the changed files in the working tree.
+    pub paths: Vec<PathBuf>,
+
+    /// Scan the content staged in the index
+    #[arg(long, conflicts_with_all = ["range", "paths"])]
+    pub staged: bool,
+
+    /// Scan the files changed in a revision range (e.g. `main..HEAD`) or by a single commit
+    #[arg(long, conflicts_with = "paths")]
+    pub range: Option<String>,
+
+    /// Scan the lines added by every commit reachable from HEAD
+    #[arg(long, conflicts_with_all = ["staged", "range", "paths"])]
+    pub history: bool,
+
+    /// With --history, skip commits already covered by the previous history scan
+    #[arg(long, requires = "history")]
+    pub resume: bool,
+
+    /// Report format
+    #[arg(long, short, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    /// Write the report to a file instead of stdout
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+/// Runs the scan and returns the process exit code.
+pub fn run(config: &Config, args: &ScanArgs) -> Result<i32> {
+    let sentinel = Sentinel::new(config);
+    for error in sentinel.config_errors() {
+        eprintln!("sgit: {}", error);
+    }
+
+    let report = if !args.paths.is_empty() {
+        let ignore_rules = IgnoreRules::load(Path::new("."));
+        let mut targets = Vec::new();
+        for path in &args.paths {
+            // Paths named explicitly are scanned even if ignored
+            walk(path, &ignore_rules, &mut targets)?;
+        }
+        scan_disk(&sentinel, targets)?
+    } else {
+        let repo = GitRepository::open(".")?;
+        let root = repo.workdir().unwrap_or(Path::new(".")).to_path_buf();
+        let sgitignore = IgnoreRules::sgitignore_only(&root);
+        let not_ignored = |path: &String| !sgitignore.is_ignored(Path::new(path), false);
+        if args.history {
+            history::scan_history(&sentinel, &repo, args.resume)?
+        } else if args.staged {
+            scan_staged(&sentinel, &repo)?
+        } else if let Some(range) = &args.range {
+            let tip = repo.range_tip(range)?;
+            let paths: Vec<String> = repo
+                .paths_in_range(range)?
+                .into_iter()
+                .filter(not_ignored)
+                .collect();
+            scan_commit(&sentinel, &repo, tip, &paths)?
+        } else {
+            let targets = repo
+                .changed_paths()?
+                .into_iter()
+                .filter(not_ignored)
+                .map(|rel| {
+                    let abs = root.join(&rel);
+                    (rel, abs)
+                })
+                .collect();
+            scan_disk(&sentinel, targets)?
+        }
+    };
+
+    let rendered = render(&report, args.format, sentinel.fail_on());
+    match &args.output {
+        Some(out) => fs::write(out, rendered)?,
+        None => print!("{}", rendered),
+    }
+
+    Ok(if report.is_blocking(sentinel.fail_on()) {
+        EXIT_BLOCKED
+    } else {
+        EXIT_CLEAN
+    })
+}
+
+pub fn render(report: &ScanReport, format: OutputFormat, fail_on: Severity) -> String {
+    match format {
+        OutputFormat::Text => report::render_text(report),
+        OutputFormat::Json => report::render_json(report, fail_on),
+        OutputFormat::Sarif => report::render_sarif(report),
+    }
+}
+
+/// Scans what is actually staged, which may differ from the working tree
+/// after partial (hunk) staging.
+pub fn scan_staged(sentinel: &Sentinel, repo: &GitRepository) -> Result<ScanReport> {
+    let root = repo.workdir().unwrap_or(Path::new("."));
+    let sgitignore = IgnoreRules::sgitignore_only(root);
+    let mut report = ScanReport::default();
+    for path in repo.staged_paths()? {
+        if sgitignore.is_ignored(Path::new(&path), false) {
+            continue;
+        }
+        report.add(&path, sentinel.scan_index_entry(repo, &path)?);
+    }
+    Ok(report)
+}
+
+/// Scans `paths` as they exist in the tree of `commit`.
+pub fn scan_commit(
+    sentinel: &Sentinel,
+    repo: &GitRepository,
+    commit: Oid,
+    paths: &[String],
+) -> Result<ScanReport> {
+    let mut report = ScanReport::default();
+    for path in paths {
+        if let Some(content) = repo.commit_blob(commit, path)? {
+            report.add(path, sentinel.scan_bytes(path, &content));
+        }
+    }
+    Ok(report)
+}
+
+/// Scans (display path, path on disk) pairs.
+fn scan_disk(sentinel: &Sentinel, targets: Vec<(String, PathBuf)>) -> Result<ScanReport> {
+    let mut report = ScanReport::default();
+    for (display, path) in targets {
+        // Deleted or renamed-away files have nothing left to scan
+        if !path.is_file() {
+            continue;
+        }
+        report.add(&display, sentinel.scan_file_as(&path, &display)?);
+    }
+    Ok(report)
+}
+
+fn walk(
+    path: &Path,
+    ignore_rules: &IgnoreRules,
+    targets: &mut Vec<(String, PathBuf)>,
+) -> Result<()> {
+    if path.is_dir() {
+        if path.file_name().map(|n| n == ".git").unwrap_or(false) {
+            return Ok(());
+        }
+        let mut entries: Vec<PathBuf> = fs::read_dir(path)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            // A symlinked directory can point back up the tree
+            .filter(|e| !is_dir_symlink(e))
+            .filter(|e| !ignore_rules.is_ignored(e, e.is_dir()))
+            .collect();
+        entries.sort();
+        for entry in entries {
+            walk(&entry, ignore_rules, targets)?;
+        }
+    } else {
+        let display = path.strip_prefix(".").unwrap_or(path);
+        targets.push((display.to_string_lossy().to_string(), path.to_path_buf()));
+    }
+    Ok(())
+}
+
+fn is_dir_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path)
+        .map(|m| m.file_type().is_symlink())
+        .unwrap_or(false)
+        && path.is_dir()
+}
@@ -0,0 +1,170 @@
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{Match, WalkBuilder};
+use std::path::{Component, Path, PathBuf};
+
+/// Project-specific ignores for Sentinel and Chronos, `.gitignore` syntax.
+pub const SGITIGNORE_FILE: &str = ".sgitignore";
+
+/// Ignore files whose change should trigger `IgnoreRules::reload`.
+pub const IGNORE_FILES: &[&str] = &[".gitignore", SGITIGNORE_FILE];
+
+/// Which paths to skip, shared by the Chronos watcher and Sentinel scans.
+pub struct IgnoreRules {
+    root: PathBuf,
+    gitignore: bool,
+    /// Lowest precedence first: global excludes, `.git/info/exclude`,
+    /// `.gitignore` files from the root down, then `.sgitignore`.
+    matchers: Vec<Gitignore>,
+    errors: Vec<String>,
+}
+
+impl IgnoreRules {
+    /// Everything git ignores, plus `.sgitignore`. For files on disk.
+    pub fn load(root: &Path) -> Self {
+        Self::build(root, true)
+    }
+
+    /// Only `.sgitignore`. For content already in git (staged, ranges,
+    /// history): `.gitignore` does not stop a file from being committed.
+    pub fn sgitignore_only(root: &Path) -> Self {
+        Self::build(root, false)
+    }
+
+    fn build(root: &Path, gitignore: bool) -> Self {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let mut rules = Self {
+            root,
+            gitignore,
+            matchers: vec![],
+            errors: vec![],
+        };
+        rules.reload();
+        rules
+    }
+
+    /// Re-reads every ignore file, e.g. after one of `IGNORE_FILES` changed.
+    pub fn reload(&mut self) {
+        self.matchers.clear();
+        self.errors.clear();
+        if self.gitignore {
+            let (global, error) = GitignoreBuilder::new(&self.root).build_global();
+            if let Some(e) = error {
+                self.errors.push(e.to_string());
+            }
+            self.matchers.push(global);
+            self.add_file(&self.root.clone(), &self.root.join(".git/info/exclude"));
+
+            // The walk itself honours .gitignore, so ignored directories are not entered
+            let mut files: Vec<PathBuf> = WalkBuilder::new(&self.root)
+                .hidden(false)
+                .filter_entry(|e| e.file_name() != ".git")
+                .build()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_name() == ".gitignore")
+                .map(|e| e.into_path())
+                .collect();
+            files.sort_by_key(|p| p.components().count());
+            for file in files {
+                let dir = file.parent().unwrap_or(&self.root).to_path_buf();
+                self.add_file(&dir, &file);
+            }
+        }
+        self.add_file(&self.root.clone(), &self.root.join(SGITIGNORE_FILE));
+    }
+
+    fn add_file(&mut self, dir: &Path, file: &Path) {
+        if !file.is_file() {
+            return;
+        }
+        let mut builder = GitignoreBuilder::new(dir);
+        if let Some(e) = builder.add(file) {
+            self.errors.push(e.to_string());
+        }
+        match builder.build() {
+            Ok(matcher) => self.matchers.push(matcher),
+            Err(e) => self.errors.push(e.to_string()),
+        }
+    }
+
+    /// Unreadable ignore files or invalid patterns, skipped at load time.
+    pub fn errors(&self) -> &[String] {
+        &self.errors
+    }
+
+    /// `path` is absolute or relative to the root. `.git` is always ignored;
+    /// nothing outside the root is.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let path = self.absolute(path);
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return false;
+        };
+        if relative.components().any(|c| c.as_os_str() == ".git") {
+            return true;
+        }
+        // The most specific file decides, a `!pattern` can re-include a path
+        for matcher in self.matchers.iter().rev() {
+            if !path.starts_with(matcher.path()) {
+                continue;
+            }
+            match matcher.matched_path_or_any_parents(&path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+        false
+    }
+
+    fn absolute(&self, path: &Path) -> PathBuf {
+        let joined = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.root.join(path)
+        };
+        // Watchers report paths like `./src/main.rs`
+        joined
+            .components()
+            .filter(|c| !matches!(c, Component::CurDir))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_ignore_sources() {
+        let root = std::env::temp_dir().join(format!("sgit-ignore-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join(".git/info")).unwrap();
+        fs::create_dir_all(root.join("src/generated")).unwrap();
+        fs::create_dir_all(root.join(".github/workflows")).unwrap();
+        fs::write(root.join(".gitignore"), "/target\n*.log\n").unwrap();
+        fs::write(root.join("src/.gitignore"), "generated/\n!keep.log\n").unwrap();
+        fs::write(root.join(".git/info/exclude"), "scratch.txt\n").unwrap();
+        fs::write(root.join(SGITIGNORE_FILE), "fixtures/\n").unwrap();
+
+        let rules = IgnoreRules::load(&root);
+        assert!(rules.errors().is_empty(), "{:?}", rules.errors());
+        assert!(rules.is_ignored(Path::new("target/debug/sgit"), false));
+        assert!(rules.is_ignored(Path::new("./build.log"), false));
+        assert!(rules.is_ignored(Path::new("src/generated/api.rs"), false));
+        assert!(rules.is_ignored(Path::new("scratch.txt"), false));
+        assert!(rules.is_ignored(Path::new("tests/fixtures/key.pem"), false));
+        assert!(rules.is_ignored(Path::new(".git/index"), false));
+        // Substrings of ignored names are not ignored
+        assert!(!rules.is_ignored(Path::new("src/target.rs"), false));
+        assert!(!rules.is_ignored(Path::new(".github/workflows/ci.yml"), false));
+        // A deeper .gitignore can re-include a path
+        assert!(!rules.is_ignored(Path::new("src/keep.log"), false));
+        assert!(rules.is_ignored(&root.join("src/other.log"), false));
+
+        let sgit_only = IgnoreRules::sgitignore_only(&root);
+        assert!(!sgit_only.is_ignored(Path::new("build.log"), false));
+        assert!(sgit_only.is_ignored(Path::new("tests/fixtures/key.pem"), false));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
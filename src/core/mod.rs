@@ -1,5 +1,8 @@
+pub mod ignore_rules;
+
 use anyhow::Result;
-use git2::{Repository, StatusOptions};
+use git2::{Delta, Diff, DiffOptions, Oid, Patch, Repository, Sort, Status, StatusOptions, Tree};
+use std::collections::HashSet;
 use std::io::Write;
 use std::path::Path;
 use std::process::{Command, Stdio};
@@ -8,6 +11,27 @@ pub struct GitRepository {
     repo: Repository,
 }
 
+/// Author and time of a commit.
+pub struct CommitMeta {
+    pub id: Oid,
+    pub author: String,
+    /// Seconds since the epoch.
+    pub time: i64,
+    pub offset_minutes: i32,
+}
+
+/// Lines a commit added to one file, relative to its parent (to every
+/// parent for merges).
+pub struct AddedLines {
+    pub path: String,
+    /// The file did not exist in the parent.
+    pub new_file: bool,
+    /// Binary files carry no lines.
+    pub binary: bool,
+    /// (1-based line number in the new file, content without newline)
+    pub lines: Vec<(usize, String)>,
+}
+
 impl GitRepository {
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
         let repo = Repository::open(path)?;
@@ -19,6 +43,10 @@ impl GitRepository {
         Ok(Self { repo })
     }
 
+    pub fn workdir(&self) -> Option<&Path> {
+        self.repo.workdir()
+    }
+
     pub fn status(&self) -> Result<Vec<(String, String)>> {
         let mut opts = StatusOptions::new();
         opts.include_untracked(true);
@@ -33,6 +61,261 @@ impl GitRepository {
         Ok(result)
     }
 
+    /// Paths with uncommitted changes (index or working tree), excluding deletions.
+    pub fn changed_paths(&self) -> Result<Vec<String>> {
+        let mut opts = StatusOptions::new();
+        opts.include_untracked(true).recurse_untracked_dirs(true);
+        let statuses = self.repo.statuses(Some(&mut opts))?;
+
+        Ok(statuses
+            .iter()
+            .filter(|e| {
+                !e.status()
+                    .intersects(Status::WT_DELETED | Status::INDEX_DELETED)
+            })
+            .filter_map(|e| e.path().map(|p| p.to_string()))
+            .collect())
+    }
+
+    /// Every path in the index.
+    pub fn tracked_paths(&self) -> Result<Vec<String>> {
+        let index = self.repo.index()?;
+        Ok(index
+            .iter()
+            .map(|e| String::from_utf8_lossy(&e.path).to_string())
+            .collect())
+    }
+
+    /// Paths added or modified in the index relative to HEAD.
+    pub fn staged_paths(&self) -> Result<Vec<String>> {
+        let head_tree = match self.repo.head() {
+            Ok(head) => Some(head.peel_to_tree()?),
+            Err(_) => None, // Initial commit: everything in the index is new
+        };
+        let diff = self
+            .repo
+            .diff_tree_to_index(head_tree.as_ref(), None, None)?;
+        Ok(Self::new_side_paths(&diff))
+    }
+
+    /// Paths added or modified in a revision range (`A..B`), or by a single commit.
+    pub fn paths_in_range(&self, spec: &str) -> Result<Vec<String>> {
+        let revspec = self.repo.revparse(spec)?;
+        let (old_tree, new_tree) = match (revspec.from(), revspec.to()) {
+            (Some(from), Some(to)) => (Some(from.peel_to_tree()?), to.peel_to_tree()?),
+            (Some(single), None) => {
+                let commit = single.peel_to_commit()?;
+                let parent_tree = match commit.parent(0) {
+                    Ok(parent) => Some(parent.tree()?),
+                    Err(_) => None, // Root commit
+                };
+                (parent_tree, commit.tree()?)
+            }
+            _ => return Err(anyhow::anyhow!("Invalid revision range: {}", spec)),
+        };
+
+        let diff = self
+            .repo
+            .diff_tree_to_tree(old_tree.as_ref(), Some(&new_tree), None)?;
+        Ok(Self::new_side_paths(&diff))
+    }
+
+    /// Commit a revision range resolves to: the `B` of `A..B`, or the single commit.
+    pub fn range_tip(&self, spec: &str) -> Result<Oid> {
+        let revspec = self.repo.revparse(spec)?;
+        let tip = revspec
+            .to()
+            .or(revspec.from())
+            .ok_or_else(|| anyhow::anyhow!("Invalid revision range: {}", spec))?;
+        Ok(tip.peel_to_commit()?.id())
+    }
+
+    /// Commits reachable from `local` but not from `remote`, oldest first.
+    /// Without a remote commit (new branch), anything already on a
+    /// remote-tracking branch is excluded instead.
+    pub fn commits_in_push(&self, local: Oid, remote: Option<Oid>) -> Result<Vec<Oid>> {
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+        revwalk.push(local)?;
+        match remote.filter(|oid| self.repo.find_commit(*oid).is_ok()) {
+            Some(oid) => revwalk.hide(oid)?,
+            None => revwalk.hide_glob("refs/remotes/*")?,
+        }
+        Ok(revwalk.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Content of `path` as currently staged in the index.
+    pub fn index_blob(&self, path: &str) -> Result<Option<Vec<u8>>> {
+        let mut index = self.repo.index()?;
+        // `git apply --cached` writes the index behind libgit2's back
+        index.read(false)?;
+        match index.get_path(Path::new(path), 0) {
+            Some(entry) => Ok(Some(self.repo.find_blob(entry.id)?.content().to_vec())),
+            None => Ok(None),
+        }
+    }
+
+    /// Content of `path` in the tree of `commit`.
+    pub fn commit_blob(&self, commit: Oid, path: &str) -> Result<Option<Vec<u8>>> {
+        let tree = self.repo.find_commit(commit)?.tree()?;
+        match tree.get_path(Path::new(path)) {
+            Ok(entry) => {
+                let object = entry.to_object(&self.repo)?;
+                Ok(object.as_blob().map(|b| b.content().to_vec()))
+            }
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// The `.git` directory.
+    pub fn git_dir(&self) -> &Path {
+        self.repo.path()
+    }
+
+    pub fn head_oid(&self) -> Result<Oid> {
+        Ok(self.repo.head()?.peel_to_commit()?.id())
+    }
+
+    /// Commits reachable from `tip`, oldest first, excluding `since` and its ancestors.
+    pub fn history(&self, tip: Oid, since: Option<Oid>) -> Result<Vec<Oid>> {
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+        revwalk.push(tip)?;
+        if let Some(since) = since {
+            revwalk.hide(since)?;
+        }
+        Ok(revwalk.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    pub fn commit_meta(&self, oid: Oid) -> Result<CommitMeta> {
+        let commit = self.repo.find_commit(oid)?;
+        let author = commit.author();
+        let when = author.when();
+        Ok(CommitMeta {
+            id: oid,
+            author: match author.email() {
+                Some(email) => format!("{} <{}>", author.name().unwrap_or(""), email),
+                None => author.name().unwrap_or("").to_string(),
+            },
+            time: when.seconds(),
+            offset_minutes: when.offset_minutes(),
+        })
+    }
+
+    /// Lines added by `oid` compared to its parent. For a merge, only the
+    /// lines that are new relative to every parent, as in a combined diff:
+    /// conflict resolutions and evil merges. Lines taken from one side are
+    /// attributed to the merged commits.
+    pub fn added_lines(&self, oid: Oid) -> Result<Vec<AddedLines>> {
+        let commit = self.repo.find_commit(oid)?;
+        let tree = commit.tree()?;
+        let parents = commit
+            .parents()
+            .map(|p| p.tree())
+            .collect::<Result<Vec<_>, _>>()?;
+        let Some((first, others)) = parents.split_first() else {
+            return self.added_lines_since(None, &tree);
+        };
+
+        let mut files = self.added_lines_since(Some(first), &tree)?;
+        for parent in others {
+            let other = self.added_lines_since(Some(parent), &tree)?;
+            files.retain_mut(|file| {
+                // Unchanged relative to this parent, nothing in it is new
+                let Some(theirs) = other.iter().find(|o| o.path == file.path) else {
+                    return false;
+                };
+                let added: HashSet<usize> = theirs.lines.iter().map(|(n, _)| *n).collect();
+                file.lines.retain(|(n, _)| added.contains(n));
+                file.new_file &= theirs.new_file;
+                file.binary |= theirs.binary;
+                file.binary || !file.lines.is_empty()
+            });
+        }
+        Ok(files)
+    }
+
+    fn added_lines_since(&self, parent: Option<&Tree>, tree: &Tree) -> Result<Vec<AddedLines>> {
+        let mut opts = DiffOptions::new();
+        opts.context_lines(0);
+        let mut diff = self
+            .repo
+            .diff_tree_to_tree(parent, Some(tree), Some(&mut opts))?;
+        // Without rename detection a moved file would count as entirely new
+        diff.find_similar(None)?;
+
+        let mut files = Vec::new();
+        for idx in 0..diff.deltas().len() {
+            let Some(patch) = Patch::from_diff(&diff, idx)? else {
+                continue;
+            };
+            let delta = patch.delta();
+            if !matches!(
+                delta.status(),
+                Delta::Added | Delta::Modified | Delta::Renamed | Delta::Copied
+            ) {
+                continue;
+            }
+            let Some(path) = delta.new_file().path() else {
+                continue;
+            };
+
+            let mut lines = Vec::new();
+            for hunk in 0..patch.num_hunks() {
+                for line in 0..patch.num_lines_in_hunk(hunk)? {
+                    let line = patch.line_in_hunk(hunk, line)?;
+                    if line.origin() != '+' {
+                        continue;
+                    }
+                    let content = String::from_utf8_lossy(line.content());
+                    lines.push((
+                        line.new_lineno().unwrap_or(0) as usize,
+                        content.trim_end_matches(['\n', '\r']).to_string(),
+                    ));
+                }
+            }
+            files.push(AddedLines {
+                path: path.to_string_lossy().to_string(),
+                new_file: delta.status() == Delta::Added,
+                binary: delta.flags().is_binary(),
+                lines,
+            });
+        }
+        Ok(files)
+    }
+
+    /// Prefix of comment lines in commit messages: `core.commentString` or
+    /// `core.commentChar`, `#` if neither is set. May be `auto`.
+    pub fn comment_prefix(&self) -> Result<String> {
+        let config = self.repo.config()?;
+        Ok(config
+            .get_string("core.commentString")
+            .or_else(|_| config.get_string("core.commentChar"))
+            .unwrap_or_else(|_| "#".to_string()))
+    }
+
+    /// Directory git runs hooks from, honouring `core.hooksPath`.
+    pub fn hooks_dir(&self) -> Result<std::path::PathBuf> {
+        if let Ok(custom) = self.repo.config()?.get_path("core.hooksPath") {
+            let base = self.repo.workdir().unwrap_or(self.repo.path());
+            return Ok(base.join(custom));
+        }
+        Ok(self.repo.path().join("hooks"))
+    }
+
+    fn new_side_paths(diff: &Diff) -> Vec<String> {
+        diff.deltas()
+            .filter(|d| {
+                matches!(
+                    d.status(),
+                    Delta::Added | Delta::Modified | Delta::Renamed | Delta::Copied
+                )
+            })
+            .filter_map(|d| d.new_file().path())
+            .map(|p| p.to_string_lossy().to_string())
+            .collect()
+    }
+
     pub fn add(&self, pathspec: &[&str]) -> Result<()> {
         let mut index = self.repo.index()?;
         index.add_all(pathspec.iter(), git2::IndexAddOption::DEFAULT, None)?;
@@ -133,9 +416,21 @@ impl GitRepository {
     }
 
     pub fn apply_patch(&self, patch: &str) -> Result<()> {
-        let mut child = Command::new("git")
-            .arg("apply")
-            .arg("--cached")
+        self.git_apply_cached(patch, false)
+    }
+
+    /// Removes a previously applied patch from the index.
+    pub fn unapply_patch(&self, patch: &str) -> Result<()> {
+        self.git_apply_cached(patch, true)
+    }
+
+    fn git_apply_cached(&self, patch: &str, reverse: bool) -> Result<()> {
+        let mut cmd = Command::new("git");
+        cmd.arg("apply").arg("--cached");
+        if reverse {
+            cmd.arg("--reverse");
+        }
+        let mut child = cmd
             .arg("--unidiff-zero") // Important for hand-crafted patches without perfect context
             .arg("-")
             .stdin(Stdio::piped())
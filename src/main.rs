@@ -1,25 +1,53 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use sgit::cli;
 use sgit::ui;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    #[arg(short, long)]
-    name: Option<String>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run the Sentinel checks without the TUI and report findings
+    Scan(cli::scan::ScanArgs),
+    /// Install, remove or run the git pre-commit / commit-msg / pre-push hooks
+    Hook(cli::hook::HookArgs),
+    /// Record current findings in .sgit-baseline.json so only new ones block
+    Baseline(cli::baseline::BaselineArgs),
+    /// Maintain the Chronos snapshot store
+    Chronos(cli::chronos::ChronosArgs),
 }
 
 fn main() {
-    let _args = Args::parse();
-    println!("SentinelGit (sgit) v0.1.0");
+    let args = Args::parse();
 
     let config = sgit::config::Config::load().unwrap_or_else(|e| {
-        eprintln!("Failed to load config: {}, using defaults", e);
+        eprintln!("sgit: failed to load config, using defaults: {:#}", e);
         sgit::config::Config::default()
     });
 
+    // Headless commands never start the terminal UI or the watcher
+    if let Some(command) = args.command {
+        let result = match command {
+            Command::Scan(scan_args) => cli::scan::run(&config, &scan_args),
+            Command::Hook(hook_args) => cli::hook::run(&config, &hook_args),
+            Command::Baseline(baseline_args) => cli::baseline::run(&config, &baseline_args),
+            Command::Chronos(chronos_args) => cli::chronos::run(&config, &chronos_args),
+        };
+        let code = result.unwrap_or_else(|e| {
+            eprintln!("sgit: {:#}", e);
+            cli::EXIT_ERROR
+        });
+        std::process::exit(code);
+    }
+
+    println!("SentinelGit (sgit) v0.1.0");
+
     // Initialize Chronos Store
-    // Using a fixed path in .git/chronos_db for now, or from config if implemented
-    let store_path = std::path::Path::new(".git/chronos_db");
+    let store_path = config.chronos.store_path();
     // Ensure parent dir exists or let sled handle it? Sled creates dir.
     // But .git might not exist if we run outside a repo.
     // For now assume we run in a repo root.
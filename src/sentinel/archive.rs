@@ -0,0 +1,382 @@
+use crate::config::ArchiveConfig;
+use crate::sentinel::binary_blocker::{self, FileType};
+use crate::sentinel::{Finding, Sentinel, Severity};
+use anyhow::Result;
+use flate2::read::GzDecoder;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+/// Extensions worth reading in full even when the file is above
+/// `max_scan_size`, since the inspector may open them.
+const ARCHIVE_EXTENSIONS: &[&str] = &[
+    "zip", "jar", "war", "ear", "apk", "whl", "nupkg", "tar", "gz", "tgz",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Zip,
+    Tar,
+    Gzip,
+}
+
+fn format(content: &[u8]) -> Option<Format> {
+    match binary_blocker::sniff_magic(content) {
+        Some(FileType::Zip) => Some(Format::Zip),
+        Some(FileType::Gzip) => Some(Format::Gzip),
+        _ if content.get(257..262) == Some(b"ustar".as_slice()) => Some(Format::Tar),
+        _ => None,
+    }
+}
+
+pub fn has_archive_extension(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .map(|ext| ARCHIVE_EXTENSIONS.contains(&ext.to_string_lossy().to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Entry paths are reported as `archive.zip!inner/path`.
+pub fn entry_path(archive: &str, entry: &str) -> String {
+    format!("{}!{}", archive, entry)
+}
+
+/// What was found inside one archive, nested archives included.
+#[derive(Debug, Default)]
+pub struct Inspection {
+    pub findings: Vec<Finding>,
+    /// Every file entry that was listed, as `archive!entry`.
+    pub entries: Vec<String>,
+    /// Entries that were not scanned, with the reason.
+    pub skipped: Vec<String>,
+}
+
+impl Inspection {
+    /// The entry findings, plus a warning if part of the archive was not
+    /// inspected: a secret could be hiding there.
+    pub fn into_findings(mut self, path: &str) -> Vec<Finding> {
+        if !self.skipped.is_empty() {
+            let mut examples = self.skipped.iter().take(3).cloned().collect::<Vec<_>>();
+            if self.skipped.len() > examples.len() {
+                examples.push("...".to_string());
+            }
+            let mut finding = Finding::file_level(
+                "archive-not-inspected",
+                "Archive partly inspected",
+                Severity::Warn,
+                path,
+                format!(
+                    "{} of {} entries not inspected: {}",
+                    self.skipped.len(),
+                    self.entries.len(),
+                    examples.join(", ")
+                ),
+            );
+            finding.remediation = Some(
+                "Raise the `[sentinel.archives]` budgets or check the archive contents by hand."
+                    .to_string(),
+            );
+            self.findings.push(finding);
+        }
+        self.findings
+    }
+}
+
+/// Lists the entries of a zip, tar or gzip archive and scans the text files
+/// in it. `None` if `content` is not an archive, an error if it is corrupt.
+pub fn inspect(
+    sentinel: &Sentinel,
+    config: &ArchiveConfig,
+    path: &str,
+    content: &[u8],
+) -> Option<Result<Inspection>> {
+    format(content)?;
+    let mut inspector = Inspector {
+        sentinel,
+        config,
+        read: 0,
+        exhausted: false,
+        inspection: Inspection::default(),
+    };
+    Some(
+        inspector
+            .archive(path, content, 1)
+            .map(|_| inspector.inspection),
+    )
+}
+
+struct Inspector<'a> {
+    sentinel: &'a Sentinel,
+    config: &'a ArchiveConfig,
+    /// Uncompressed bytes read so far.
+    read: u64,
+    /// Set once `max_entries` or `max_total_size` is reached.
+    exhausted: bool,
+    inspection: Inspection,
+}
+
+impl Inspector<'_> {
+    fn remaining(&self) -> u64 {
+        self.config.max_total_size.0.saturating_sub(self.read)
+    }
+
+    /// Opens an archive `depth` levels deep (1 for the scanned file itself).
+    fn archive(&mut self, path: &str, content: &[u8], depth: usize) -> Result<()> {
+        match format(content) {
+            Some(Format::Zip) => {
+                let mut zip = zip::ZipArchive::new(Cursor::new(content))?;
+                for i in 0..zip.len() {
+                    let mut file = zip.by_index(i)?;
+                    if file.is_dir() {
+                        continue;
+                    }
+                    let name = file.name().to_string();
+                    let size = file.size();
+                    self.entry(path, &name, size, &mut file, depth)?;
+                    if self.exhausted {
+                        break;
+                    }
+                }
+            }
+            Some(Format::Tar) => {
+                let mut tar = tar::Archive::new(content);
+                for entry in tar.entries()? {
+                    let mut entry = entry?;
+                    if !entry.header().entry_type().is_file() {
+                        continue;
+                    }
+                    let name = entry.path()?.to_string_lossy().to_string();
+                    let size = entry.size();
+                    self.entry(path, &name, size, &mut entry, depth)?;
+                    if self.exhausted {
+                        break;
+                    }
+                }
+            }
+            Some(Format::Gzip) => {
+                // A single stream, usually a tarball that is listed as if it were one archive
+                let limit = self.remaining();
+                let mut data = Vec::new();
+                GzDecoder::new(content)
+                    .take(limit + 1)
+                    .read_to_end(&mut data)?;
+                if data.len() as u64 > limit {
+                    self.exhausted = true;
+                    self.inspection
+                        .skipped
+                        .push(format!("{} (max_total_size)", path));
+                    return Ok(());
+                }
+                self.read += data.len() as u64;
+                match format(&data) {
+                    Some(Format::Tar) => self.archive(path, &data, depth)?,
+                    _ => {
+                        let name = Path::new(path)
+                            .file_stem()
+                            .map(|s| s.to_string_lossy().to_string())
+                            .unwrap_or_default();
+                        let inner = entry_path(path, &name);
+                        self.inspection.entries.push(inner.clone());
+                        self.scan(&inner, &data, depth);
+                    }
+                }
+            }
+            None => {}
+        }
+        Ok(())
+    }
+
+    fn entry(
+        &mut self,
+        archive: &str,
+        name: &str,
+        size: u64,
+        reader: &mut dyn Read,
+        depth: usize,
+    ) -> Result<()> {
+        let inner = entry_path(archive, name);
+        if self.inspection.entries.len() >= self.config.max_entries {
+            self.exhausted = true;
+            self.inspection
+                .skipped
+                .push(format!("{} (max_entries)", inner));
+            return Ok(());
+        }
+        self.inspection.entries.push(inner.clone());
+
+        // The declared size can lie, so never read more than the budget allows
+        let max_entry_size = self.config.max_entry_size.0;
+        if size > max_entry_size {
+            self.inspection
+                .skipped
+                .push(format!("{} (max_entry_size)", inner));
+            return Ok(());
+        }
+        let limit = max_entry_size.min(self.remaining());
+        let mut data = Vec::new();
+        reader.take(limit + 1).read_to_end(&mut data)?;
+        if data.len() as u64 > limit {
+            let reason = if limit < max_entry_size {
+                self.exhausted = true;
+                "max_total_size"
+            } else {
+                "max_entry_size"
+            };
+            self.inspection
+                .skipped
+                .push(format!("{} ({})", inner, reason));
+            return Ok(());
+        }
+        self.read += data.len() as u64;
+        self.scan(&inner, &data, depth);
+        Ok(())
+    }
+
+    fn scan(&mut self, inner: &str, data: &[u8], depth: usize) {
+        if format(data).is_none() {
+            let findings = self.sentinel.scan_archive_entry(inner, data);
+            self.inspection.findings.extend(findings);
+            return;
+        }
+        if depth >= self.config.max_depth {
+            self.inspection
+                .skipped
+                .push(format!("{} (max_depth)", inner));
+            return;
+        }
+        if let Err(e) = self.archive(inner, data, depth + 1) {
+            self.inspection.skipped.push(format!("{} ({})", inner, e));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ByteSize, Config, PolicyOverride};
+    use std::io::Write;
+
+    fn secret() -> String {
+        // Assembled at runtime so this file does not trip the scanner itself
+        format!("AKIA{}", "Z7Q4M2XK9P3LR6TW")
+    }
+
+    fn zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer
+                .start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn tar_gz(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(gz);
+        for (name, content) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, *content).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn sentinel() -> (Sentinel, ArchiveConfig) {
+        let mut config = Config::default();
+        config.sentinel.archives.enabled = true;
+        let archives = config.sentinel.archives.clone();
+        (Sentinel::new(&config), archives)
+    }
+
+    #[test]
+    fn test_zip_entries() {
+        let (sentinel, config) = sentinel();
+        let env = format!("AWS_KEY={}\n", secret());
+        let content = zip(&[
+            ("README.md", b"# fixtures\n"),
+            ("config/.env", env.as_bytes()),
+            ("logo.png", b"\x89PNG\r\n\x1a\n\0\0"),
+        ]);
+
+        let inspection = inspect(&sentinel, &config, "fixtures.zip", &content)
+            .unwrap()
+            .unwrap();
+        assert_eq!(inspection.entries.len(), 3);
+        assert!(inspection.skipped.is_empty());
+        let findings = inspection.into_findings("fixtures.zip");
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].rule_id, "aws-access-key-id");
+        assert_eq!(findings[0].path, "fixtures.zip!config/.env");
+        assert_eq!(findings[0].line, Some(1));
+        assert_eq!(findings[1].rule_id, "binary-file");
+        assert_eq!(findings[1].path, "fixtures.zip!logo.png");
+    }
+
+    #[test]
+    fn test_binary_entry_overrides() {
+        let mut config = Config::default();
+        config.sentinel.archives.enabled = true;
+        config.sentinel.overrides = vec![PolicyOverride {
+            paths: vec!["*.zip!assets/**".to_string()],
+            disabled_rules: vec!["binary-file".to_string()],
+            severities: Default::default(),
+            binary_extensions: vec![],
+            max_scan_size: None,
+            large_file_warn: None,
+            large_file_block: None,
+        }];
+        let sentinel = Sentinel::new(&config);
+        let png: &[u8] = b"\x89PNG\r\n\x1a\n\0\0";
+        let content = zip(&[("assets/logo.png", png), ("bin/tool.png", png)]);
+
+        let findings = inspect(&sentinel, &config.sentinel.archives, "app.zip", &content)
+            .unwrap()
+            .unwrap()
+            .into_findings("app.zip");
+        let paths: Vec<_> = findings.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["app.zip!bin/tool.png"]);
+    }
+
+    #[test]
+    fn test_nested_tar_gz() {
+        let (sentinel, mut config) = sentinel();
+        let key = format!("key = \"{}\"\n", secret());
+        let inner = zip(&[("settings.toml", key.as_bytes())]);
+        let content = tar_gz(&[("bundle/inner.jar", &inner)]);
+
+        let inspection = inspect(&sentinel, &config, "dist.tar.gz", &content)
+            .unwrap()
+            .unwrap();
+        let paths: Vec<_> = inspection.findings.iter().map(|f| &f.path).collect();
+        assert_eq!(paths, ["dist.tar.gz!bundle/inner.jar!settings.toml"]);
+
+        // Past the depth budget the inner archive is skipped, and that warns
+        config.max_depth = 1;
+        let findings = inspect(&sentinel, &config, "dist.tar.gz", &content)
+            .unwrap()
+            .unwrap()
+            .into_findings("dist.tar.gz");
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule_id, "archive-not-inspected");
+        assert!(findings[0].message.contains("max_depth"));
+    }
+
+    #[test]
+    fn test_entry_budget() {
+        let (sentinel, mut config) = sentinel();
+        config.max_entry_size = ByteSize(8);
+        let content = zip(&[("small.txt", b"ok"), ("big.txt", b"far too large")]);
+
+        let inspection = inspect(&sentinel, &config, "a.zip", &content)
+            .unwrap()
+            .unwrap();
+        assert_eq!(inspection.skipped, ["a.zip!big.txt (max_entry_size)"]);
+        assert!(inspect(&sentinel, &config, "a.txt", b"plain").is_none());
+        assert!(inspect(&sentinel, &config, "a.zip", b"PK\x03\x04broken")
+            .unwrap()
+            .is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use crate::sentinel::finding::{Finding, Suppression};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Acknowledged findings, committed at the repository root.
+pub const BASELINE_FILE: &str = ".sgit-baseline.json";
+
+const BASELINE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub fingerprint: String,
+    /// Informational only, matching is done on the fingerprint.
+    pub rule_id: String,
+    pub path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Baseline {
+    pub version: u32,
+    pub entries: Vec<BaselineEntry>,
+    #[serde(skip)]
+    fingerprints: HashSet<String>,
+}
+
+impl Default for Baseline {
+    fn default() -> Self {
+        Self {
+            version: BASELINE_VERSION,
+            entries: vec![],
+            fingerprints: HashSet::new(),
+        }
+    }
+}
+
+impl Baseline {
+    /// Loads the baseline, or an empty one if the file does not exist.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let mut baseline: Baseline = serde_json::from_str(&fs::read_to_string(path)?)?;
+        baseline.fingerprints = baseline
+            .entries
+            .iter()
+            .map(|e| e.fingerprint.clone())
+            .collect();
+        Ok(baseline)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        // Trailing newline keeps the file friendly to diffs and editors
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json + "\n")?;
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, finding: &Finding) -> bool {
+        self.fingerprints.contains(&finding.fingerprint)
+    }
+
+    /// Adds a finding, returns false if it was already baselined.
+    pub fn insert(&mut self, finding: &Finding) -> bool {
+        if !self.fingerprints.insert(finding.fingerprint.clone()) {
+            return false;
+        }
+        self.entries.push(BaselineEntry {
+            fingerprint: finding.fingerprint.clone(),
+            rule_id: finding.rule_id.clone(),
+            path: finding.path.clone(),
+        });
+        true
+    }
+
+    /// Drops entries whose fingerprint is not in `keep`, returns how many.
+    pub fn retain(&mut self, keep: &HashSet<String>) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|e| keep.contains(&e.fingerprint));
+        self.fingerprints.retain(|f| keep.contains(f));
+        before - self.entries.len()
+    }
+
+    /// Sorted by path then rule so regenerating gives minimal diffs.
+    pub fn sort(&mut self) {
+        self.entries.sort_by(|a, b| {
+            (&a.path, &a.rule_id, &a.fingerprint).cmp(&(&b.path, &b.rule_id, &b.fingerprint))
+        });
+    }
+
+    /// Marks baselined findings as suppressed.
+    pub fn apply(&self, findings: &mut [Finding]) {
+        if self.is_empty() {
+            return;
+        }
+        for finding in findings.iter_mut() {
+            if finding.suppressed.is_none() && self.contains(finding) {
+                finding.suppressed = Some(Suppression::Baseline);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sentinel::Severity;
+
+    fn finding(secret: &str) -> Finding {
+        let text = format!("token = {}", secret);
+        Finding::at_span(
+            "tok",
+            "Token",
+            Severity::Block,
+            "a.env",
+            &text,
+            8,
+            text.len(),
+        )
+    }
+
+    #[test]
+    fn test_only_known_findings_are_suppressed() {
+        let mut baseline = Baseline::default();
+        baseline.insert(&finding("old-secret"));
+
+        let mut findings = vec![finding("old-secret"), finding("new-secret")];
+        baseline.apply(&mut findings);
+        assert_eq!(findings[0].suppressed, Some(Suppression::Baseline));
+        assert_eq!(findings[1].suppressed, None);
+    }
+
+    #[test]
+    fn test_roundtrip_and_prune() {
+        let dir = std::env::temp_dir().join(format!("sgit-baseline-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(BASELINE_FILE);
+
+        let mut baseline = Baseline::default();
+        assert!(baseline.insert(&finding("a")));
+        assert!(!baseline.insert(&finding("a")));
+        baseline.insert(&finding("b"));
+        baseline.save(&path).unwrap();
+
+        let mut loaded = Baseline::load(&path).unwrap();
+        assert!(loaded.contains(&finding("b")));
+        let keep: HashSet<String> = [finding("a").fingerprint].into_iter().collect();
+        assert_eq!(loaded.retain(&keep), 1);
+        assert!(!loaded.contains(&finding("b")));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
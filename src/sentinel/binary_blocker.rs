@@ -1,30 +1,237 @@
-use std::fs::File;
-use std::io::Read;
+use std::fmt;
 use std::path::Path;
 
-pub fn is_binary(path_str: &str, extensions: &[String]) -> bool {
-    let path = Path::new(path_str);
+/// How many leading bytes are inspected when sniffing content.
+pub const SNIFF_LEN: usize = 1024;
 
-    // 1. Check extension
-    if let Some(ext) = path.extension() {
-        let ext_str = ext.to_string_lossy().to_lowercase();
-        if extensions.iter().any(|e| e == &ext_str) {
-            return true;
+/// Binary formats recognised by their magic number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileType {
+    Elf,
+    Pe,
+    MachO,
+    /// `CA FE BA BE` is shared by Mach-O universal binaries and Java classes.
+    FatMachOOrClass,
+    Zip,
+    Png,
+    Jpeg,
+    Pdf,
+    Sqlite,
+    Gzip,
+}
+
+impl fmt::Display for FileType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FileType::Elf => "ELF executable",
+            FileType::Pe => "PE executable",
+            FileType::MachO => "Mach-O executable",
+            FileType::FatMachOOrClass => "Mach-O universal binary or Java class",
+            FileType::Zip => "ZIP archive (zip/jar/docx)",
+            FileType::Png => "PNG image",
+            FileType::Jpeg => "JPEG image",
+            FileType::Pdf => "PDF document",
+            FileType::Sqlite => "SQLite database",
+            FileType::Gzip => "gzip archive",
+        })
+    }
+}
+
+/// Why a file was classified as binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BinaryKind {
+    Extension(String),
+    Magic(FileType),
+    /// Unknown format, but it has NUL bytes and no UTF-16/32 byte order mark.
+    NulBytes,
+}
+
+impl fmt::Display for BinaryKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BinaryKind::Extension(ext) => write!(f, ".{} extension", ext),
+            BinaryKind::Magic(file_type) => write!(f, "{}", file_type),
+            BinaryKind::NulBytes => write!(f, "NUL bytes in the first {} bytes", SNIFF_LEN),
         }
     }
+}
+
+/// Unicode encodings identified by their byte order mark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf16Le,
+    Utf16Be,
+    Utf32Le,
+    Utf32Be,
+}
 
-    // 2. Check content (Null bytes)
-    if let Ok(mut file) = File::open(path) {
-        let mut buffer = [0; 1024];
-        if let Ok(n) = file.read(&mut buffer) {
-            // Check for null bytes in the first 1024 bytes
-            // A common heuristic is that text files don't contain null bytes
-            // (except maybe UTF-16, but we assume UTF-8/ASCII for now)
-            if buffer[..n].contains(&0) {
-                return true;
-            }
+impl Encoding {
+    fn from_bom(content: &[u8]) -> Option<(Encoding, usize)> {
+        // UTF-32LE first, its BOM starts with the UTF-16LE one
+        match content {
+            [0xFF, 0xFE, 0, 0, ..] => Some((Encoding::Utf32Le, 4)),
+            [0, 0, 0xFE, 0xFF, ..] => Some((Encoding::Utf32Be, 4)),
+            [0xFF, 0xFE, ..] => Some((Encoding::Utf16Le, 2)),
+            [0xFE, 0xFF, ..] => Some((Encoding::Utf16Be, 2)),
+            _ => None,
         }
     }
+}
+
+/// Classifies content as binary by extension, then magic number, then NUL
+/// bytes. `None` means the content should be scanned as text.
+pub fn detect(path_str: &str, content: &[u8], extensions: &[String]) -> Option<BinaryKind> {
+    if has_binary_extension(path_str, extensions) {
+        let ext = Path::new(path_str).extension()?;
+        return Some(BinaryKind::Extension(ext.to_string_lossy().to_lowercase()));
+    }
+    if let Some(file_type) = sniff_magic(content) {
+        return Some(BinaryKind::Magic(file_type));
+    }
+    if looks_binary(content) {
+        return Some(BinaryKind::NulBytes);
+    }
+    None
+}
 
+pub fn has_binary_extension(path_str: &str, extensions: &[String]) -> bool {
+    if let Some(ext) = Path::new(path_str).extension() {
+        let ext_str = ext.to_string_lossy().to_lowercase();
+        return extensions.iter().any(|e| e == &ext_str);
+    }
     false
 }
+
+/// Checks for null bytes in the first 1024 bytes, unless a byte order mark
+/// says the content is UTF-16/32 text (which is full of them).
+pub fn looks_binary(content: &[u8]) -> bool {
+    if Encoding::from_bom(content).is_some() {
+        return false;
+    }
+    let head = &content[..content.len().min(SNIFF_LEN)];
+    head.contains(&0)
+}
+
+/// Identifies common binary formats from their leading bytes.
+pub fn sniff_magic(content: &[u8]) -> Option<FileType> {
+    let file_type = match content {
+        [0x7F, b'E', b'L', b'F', ..] => FileType::Elf,
+        [b'M', b'Z', ..] if has_pe_header(content) => FileType::Pe,
+        [0xFE, 0xED, 0xFA, 0xCE | 0xCF, ..] | [0xCE | 0xCF, 0xFA, 0xED, 0xFE, ..] => {
+            FileType::MachO
+        }
+        [0xCA, 0xFE, 0xBA, 0xBE, ..] => FileType::FatMachOOrClass,
+        [b'P', b'K', 3, 4, ..] | [b'P', b'K', 5, 6, ..] | [b'P', b'K', 7, 8, ..] => FileType::Zip,
+        [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n', ..] => FileType::Png,
+        [0xFF, 0xD8, 0xFF, ..] => FileType::Jpeg,
+        _ if content.starts_with(b"%PDF-") => FileType::Pdf,
+        _ if content.starts_with(b"SQLite format 3\0") => FileType::Sqlite,
+        [0x1F, 0x8B, ..] => FileType::Gzip,
+        _ => return None,
+    };
+    Some(file_type)
+}
+
+/// `MZ` alone is too weak (plain text can start with it), so also require the
+/// `PE\0\0` signature the DOS header points to.
+fn has_pe_header(content: &[u8]) -> bool {
+    let Some(offset) = content.get(0x3C..0x40) else {
+        return false;
+    };
+    let offset = u32::from_le_bytes([offset[0], offset[1], offset[2], offset[3]]) as usize;
+    content.get(offset..offset + 4) == Some(b"PE\0\0".as_slice())
+}
+
+/// Decodes UTF-16/32 text that starts with a byte order mark. `None` for
+/// anything else, including UTF-8.
+pub fn decode_utf(content: &[u8]) -> Option<(Encoding, String)> {
+    let (encoding, bom_len) = Encoding::from_bom(content)?;
+    let body = &content[bom_len..];
+    let text = match encoding {
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            let units = body.chunks_exact(2).map(|c| match encoding {
+                Encoding::Utf16Le => u16::from_le_bytes([c[0], c[1]]),
+                _ => u16::from_be_bytes([c[0], c[1]]),
+            });
+            char::decode_utf16(units)
+                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect()
+        }
+        Encoding::Utf32Le | Encoding::Utf32Be => body
+            .chunks_exact(4)
+            .map(|c| {
+                let code = match encoding {
+                    Encoding::Utf32Le => u32::from_le_bytes([c[0], c[1], c[2], c[3]]),
+                    _ => u32::from_be_bytes([c[0], c[1], c[2], c[3]]),
+                };
+                char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+            })
+            .collect(),
+    };
+    Some((encoding, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(text: &str) -> Vec<u8> {
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(text.encode_utf16().flat_map(|u| u.to_le_bytes()));
+        bytes
+    }
+
+    #[test]
+    fn test_magic_numbers() {
+        assert_eq!(sniff_magic(b"\x7FELF\x02\x01\x01"), Some(FileType::Elf));
+        assert_eq!(sniff_magic(b"PK\x03\x04\x14\x00"), Some(FileType::Zip));
+        assert_eq!(sniff_magic(b"%PDF-1.7\n"), Some(FileType::Pdf));
+        assert_eq!(
+            sniff_magic(b"SQLite format 3\0\x10\x00"),
+            Some(FileType::Sqlite)
+        );
+        assert_eq!(sniff_magic(b"\xCF\xFA\xED\xFE\x07"), Some(FileType::MachO));
+
+        let mut pe = vec![0u8; 0x80];
+        pe[..2].copy_from_slice(b"MZ");
+        pe[0x3C] = 0x40;
+        pe[0x40..0x44].copy_from_slice(b"PE\0\0");
+        assert_eq!(sniff_magic(&pe), Some(FileType::Pe));
+        assert_eq!(sniff_magic(b"MZ is also how this sentence starts"), None);
+        assert_eq!(sniff_magic(b"fn main() {}"), None);
+    }
+
+    #[test]
+    fn test_detect_reason() {
+        let exts = vec!["exe".to_string()];
+        assert_eq!(
+            detect("tool.EXE", b"", &exts),
+            Some(BinaryKind::Extension("exe".to_string()))
+        );
+        // No extension and no NUL byte early on, still caught by its magic
+        assert_eq!(
+            detect("bin/tool", b"\x7FELF\x02\x01\x01\x03", &exts),
+            Some(BinaryKind::Magic(FileType::Elf))
+        );
+        assert_eq!(
+            detect("data", b"abc\0def", &exts),
+            Some(BinaryKind::NulBytes)
+        );
+        assert_eq!(detect("notes.txt", b"plain text", &exts), None);
+    }
+
+    #[test]
+    fn test_utf16_is_text() {
+        let content = utf16le("password = \"hunter2\"\n");
+        assert!(!looks_binary(&content));
+        assert_eq!(detect("notes.txt", &content, &[]), None);
+        let (encoding, text) = decode_utf(&content).unwrap();
+        assert_eq!(encoding, Encoding::Utf16Le);
+        assert_eq!(text, "password = \"hunter2\"\n");
+
+        let mut utf32be = vec![0, 0, 0xFE, 0xFF];
+        utf32be.extend("key".chars().flat_map(|c| (c as u32).to_be_bytes()));
+        assert_eq!(decode_utf(&utf32be).unwrap().1, "key");
+        assert!(decode_utf(b"plain utf-8").is_none());
+    }
+}
@@ -1,4 +1,7 @@
+use lazy_static::lazy_static;
+use regex::Regex;
 use std::collections::HashMap;
+use std::fmt;
 
 /// Calculates the Shannon entropy of a byte slice.
 /// Returns a value between 0.0 and 8.0.
@@ -24,10 +27,134 @@ pub fn calculate_entropy(data: &[u8]) -> f64 {
     entropy
 }
 
-/// Threshold for what we consider "high entropy" (potential secret/key).
-/// Standard English text is usually around 3.5 - 4.5.
-/// Base64 encoded secrets often exceed 5.5 or 6.0.
-pub const HIGH_ENTROPY_THRESHOLD: f64 = 6.0;
+/// Thresholds for a single token, per charset. A token of n characters can
+/// reach at most log2(n) bits, so these only make sense together with
+/// `MIN_TOKEN_LEN`. Random base64 of 32 chars averages ~4.5, camelCase
+/// identifiers stay around 4.0; random hex of 20+ chars is above 3.3.
+pub const BASE64_ENTROPY_THRESHOLD: f64 = 4.3;
+pub const HEX_ENTROPY_THRESHOLD: f64 = 3.0;
+
+/// Shorter tokens cannot be told apart from ordinary words.
+pub const MIN_TOKEN_LEN: usize = 20;
+
+/// Longer runs are embedded data (data URIs, inlined assets), not credentials.
+const MAX_TOKEN_LEN: usize = 256;
+
+/// Files made of checksums by design.
+const LOCKFILES: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "go.sum",
+    "poetry.lock",
+    "Gemfile.lock",
+    "composer.lock",
+];
+
+lazy_static! {
+    /// Candidate strings: quoted literals, assignment values, then bare runs.
+    static ref CANDIDATE_RE: Regex = Regex::new(
+        r#""([^"\n]+)"|'([^'\n]+)'|`([^`\n]+)`|[=:]\s*([^\s"'`,;]+)|([A-Za-z0-9+/_\-]+=*)"#
+    )
+    .unwrap();
+    static ref RUN_RE: Regex = Regex::new(r"[A-Za-z0-9+/_\-]+=*").unwrap();
+    /// Git object ids and image digests where they are pinned or linked:
+    /// `action@<sha>`, `image@sha256:<digest>`, `.../commit/<sha>`.
+    static ref DIGEST_RE: Regex =
+        Regex::new(r"(?:@|sha256:|/commits?/)([0-9a-fA-F]{64}|[0-9a-fA-F]{40})\b").unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Charset {
+    Hex,
+    Base64,
+}
+
+impl Charset {
+    fn of(token: &str) -> Self {
+        if token.bytes().all(|b| b.is_ascii_hexdigit()) {
+            Charset::Hex
+        } else {
+            Charset::Base64
+        }
+    }
+
+    pub fn threshold(self) -> f64 {
+        match self {
+            Charset::Hex => HEX_ENTROPY_THRESHOLD,
+            Charset::Base64 => BASE64_ENTROPY_THRESHOLD,
+        }
+    }
+}
+
+impl fmt::Display for Charset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Charset::Hex => f.write_str("hex"),
+            Charset::Base64 => f.write_str("base64"),
+        }
+    }
+}
+
+/// A high-entropy token at `text[start..end]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntropyToken {
+    pub start: usize,
+    pub end: usize,
+    pub charset: Charset,
+    pub entropy: f64,
+}
+
+/// True for files whose content is expected to be all hashes.
+pub fn is_excluded(path: &str) -> bool {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    LOCKFILES.contains(&name)
+}
+
+/// Splits `text` into candidate tokens and returns those whose entropy is
+/// above the threshold for their charset.
+pub fn find_high_entropy_tokens(text: &str) -> Vec<EntropyToken> {
+    let digests: Vec<(usize, usize)> = DIGEST_RE
+        .captures_iter(text)
+        .filter_map(|caps| caps.get(1))
+        .map(|m| (m.start(), m.end()))
+        .collect();
+    let mut tokens = Vec::new();
+    for caps in CANDIDATE_RE.captures_iter(text) {
+        let Some(candidate) = (1..=5).find_map(|i| caps.get(i)) else {
+            continue;
+        };
+        // A literal can hold several runs, e.g. "Bearer <token>"
+        for run in RUN_RE.find_iter(candidate.as_str()) {
+            let token = run.as_str();
+            if token.len() < MIN_TOKEN_LEN || token.len() > MAX_TOKEN_LEN {
+                continue;
+            }
+            // Long identifiers and paths rarely contain digits, keys almost always do
+            if !token.bytes().any(|b| b.is_ascii_digit()) {
+                continue;
+            }
+            let start = candidate.start() + run.start();
+            let end = candidate.start() + run.end();
+            // Commit hashes and digests are random by nature but public
+            if digests.iter().any(|&(s, e)| s < end && start < e) {
+                continue;
+            }
+            let charset = Charset::of(token);
+            let entropy = calculate_entropy(token.as_bytes());
+            if entropy > charset.threshold() {
+                tokens.push(EntropyToken {
+                    start,
+                    end,
+                    charset,
+                    entropy,
+                });
+            }
+        }
+    }
+    tokens
+}
 
 #[cfg(test)]
 mod tests {
@@ -54,6 +181,60 @@ mod tests {
         let entropy = calculate_entropy(data);
         // This might not be super high because it's hex (limited charset), but let's check
         // A real random byte array would be better
-        assert!(entropy > 3.0); 
+        assert!(entropy > 3.0);
+    }
+
+    #[test]
+    fn test_token_in_source_file() {
+        let text = "fn main() {\n    let client = Client::new();\n    \
+                    let secret = \"wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY\";\n}\n";
+        let tokens = find_high_entropy_tokens(text);
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(
+            &text[tokens[0].start..tokens[0].end],
+            "wJalrXUtnFEMI/K7MDENG/bPxRfiCYEXAMPLEKEY"
+        );
+        assert_eq!(tokens[0].charset, Charset::Base64);
+    }
+
+    #[test]
+    fn test_hex_and_assignment_values() {
+        let text = "token=8f9d23a1c5b6e7f8901234567890abcdef\nname: plain_value_2\n";
+        let tokens = find_high_entropy_tokens(text);
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].charset, Charset::Hex);
+        assert_eq!(tokens[0].start, 6);
+    }
+
+    #[test]
+    fn test_pinned_shas_and_digests_are_not_tokens() {
+        let sha = "b4ffde65f46336ab88eb53be808477a3936bae11";
+        let digest = "c5b1261d6d3e43071626931fc004f70149baeba2c8ec672bd4f27761f8e1ad6b";
+        let action = format!("steps:\n  - uses: actions/checkout@{}\n", sha);
+        let changelog = format!(
+            "- Fix parser ([b4ffde6](https://github.com/sgit-dev/sentinel-git/commit/{}))\n",
+            sha
+        );
+        let dockerfile = format!("FROM alpine@sha256:{}\n", digest);
+        assert!(find_high_entropy_tokens(&action).is_empty());
+        assert!(find_high_entropy_tokens(&changelog).is_empty());
+        assert!(find_high_entropy_tokens(&dockerfile).is_empty());
+
+        // The same hex outside of a digest context is still a token
+        assert_eq!(find_high_entropy_tokens(&format!("token={}", sha)).len(), 1);
+    }
+
+    #[test]
+    fn test_identifiers_are_not_tokens() {
+        let text = "AbstractSingletonProxyFactoryBean test_helper_function_name_v2 \
+                    src/sentinel/regex_guard.rs";
+        assert!(find_high_entropy_tokens(text).is_empty());
+    }
+
+    #[test]
+    fn test_lockfiles_are_excluded() {
+        assert!(is_excluded("Cargo.lock"));
+        assert!(is_excluded("web/package-lock.json"));
+        assert!(!is_excluded("src/lock.rs"));
     }
 }
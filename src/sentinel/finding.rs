@@ -0,0 +1,324 @@
+use crate::sentinel::validators::Validation;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+/// How serious a finding is. Ordered so that `Block > Warn > Info`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warn,
+    Block,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Severity::Info => "info",
+            Severity::Warn => "warn",
+            Severity::Block => "block",
+        };
+        f.write_str(s)
+    }
+}
+
+/// What kind of data a finding is about, so compliance reports can tell
+/// leaked credentials from personal data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    /// Credentials, keys and tokens.
+    #[default]
+    Secret,
+    /// Personal data: emails, phone numbers, national IDs, bank and card numbers.
+    Pii,
+    /// Properties of the file as a whole (binary, size, archives).
+    File,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Category::Secret => "secret",
+            Category::Pii => "pii",
+            Category::File => "file",
+        };
+        f.write_str(s)
+    }
+}
+
+/// Byte range of a match within the scanned content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Why a finding no longer blocks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Suppression {
+    /// Acknowledged in the baseline file.
+    Baseline,
+    /// Allowed by an `sgit:allow` marker in the source.
+    Inline { reason: Option<String> },
+}
+
+impl fmt::Display for Suppression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Suppression::Baseline => f.write_str("baseline"),
+            Suppression::Inline { reason: Some(r) } => write!(f, "sgit:allow, {}", r),
+            Suppression::Inline { reason: None } => f.write_str("sgit:allow"),
+        }
+    }
+}
+
+/// The commit that introduced a finding, for history scans.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommitInfo {
+    pub id: String,
+    pub author: String,
+    /// RFC 3339, in the author's timezone.
+    pub date: String,
+}
+
+impl CommitInfo {
+    pub fn short_id(&self) -> &str {
+        &self.id[..self.id.len().min(7)]
+    }
+}
+
+/// A single Sentinel hit. File-level checks (binary, size) have no span.
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub rule_id: String,
+    pub name: String,
+    pub category: Category,
+    pub severity: Severity,
+    pub path: String,
+    pub span: Option<Span>,
+    /// 1-based line of the match.
+    pub line: Option<usize>,
+    /// 1-based column (in characters) of the match.
+    pub column: Option<usize>,
+    /// The matched line with the secret itself masked.
+    pub excerpt: Option<String>,
+    pub message: String,
+    pub remediation: Option<String>,
+    /// Result of the rule's validator, if it has one and it was conclusive.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validation: Option<Validation>,
+    /// Stable identity used by the baseline: rule id, path and a hash of the
+    /// matched secret, or of the whole content for file-level findings.
+    /// Independent of the line number.
+    pub fingerprint: String,
+    pub suppressed: Option<Suppression>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<CommitInfo>,
+}
+
+impl Finding {
+    /// A finding about the file as a whole.
+    pub fn file_level(
+        rule_id: &str,
+        name: &str,
+        severity: Severity,
+        path: &str,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            rule_id: rule_id.to_string(),
+            name: name.to_string(),
+            category: Category::File,
+            severity,
+            path: path.to_string(),
+            span: None,
+            line: None,
+            column: None,
+            excerpt: None,
+            message: message.into(),
+            remediation: None,
+            validation: None,
+            fingerprint: fingerprint(rule_id, path, ""),
+            suppressed: None,
+            commit: None,
+        }
+    }
+
+    /// A finding for `text[start..end]`, with location and redacted excerpt filled in.
+    pub fn at_span(
+        rule_id: &str,
+        name: &str,
+        severity: Severity,
+        path: &str,
+        text: &str,
+        start: usize,
+        end: usize,
+    ) -> Self {
+        let (line, column) = line_col(text, start);
+        Self {
+            rule_id: rule_id.to_string(),
+            name: name.to_string(),
+            category: Category::Secret,
+            severity,
+            path: path.to_string(),
+            span: Some(Span { start, end }),
+            line: Some(line),
+            column: Some(column),
+            excerpt: Some(redacted_excerpt(text, start, end)),
+            message: format!("{} detected", name),
+            remediation: None,
+            validation: None,
+            fingerprint: fingerprint(rule_id, path, &text[start..end]),
+            suppressed: None,
+            commit: None,
+        }
+    }
+
+    /// Ties a file-level finding to the content it was raised for, so that
+    /// baselining one binary does not accept any other file at its path.
+    pub fn bind_content(&mut self, content_hash: &str) {
+        if self.span.is_none() {
+            self.fingerprint = fingerprint(&self.rule_id, &self.path, content_hash);
+        }
+    }
+
+    /// True if `secret` is what this finding matched, by its fingerprint.
+    pub fn is_match(&self, secret: &str) -> bool {
+        self.span.is_some() && self.fingerprint == fingerprint(&self.rule_id, &self.path, secret)
+    }
+
+    pub fn is_suppressed(&self) -> bool {
+        self.suppressed.is_some()
+    }
+
+    /// `path:line:col`, or just `path` for file-level findings.
+    pub fn location(&self) -> String {
+        match (self.line, self.column) {
+            (Some(line), Some(col)) => format!("{}:{}:{}", self.path, line, col),
+            _ => self.path.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(commit) = &self.commit {
+            write!(f, "{} ", commit.short_id())?;
+        }
+        write!(
+            f,
+            "{} [{}] {}: {}",
+            self.location(),
+            self.severity,
+            self.rule_id,
+            self.message
+        )
+    }
+}
+
+fn fingerprint(rule_id: &str, path: &str, secret: &str) -> String {
+    let secret_hash = hex(&Sha256::digest(secret.as_bytes()));
+    let digest = Sha256::digest(format!("{}\0{}\0{}", rule_id, path, secret_hash).as_bytes());
+    hex(&digest)
+}
+
+/// SHA-256 of file content, for `Finding::bind_content`.
+pub fn content_hash(content: &[u8]) -> String {
+    hex(&Sha256::digest(content))
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 1-based (line, column) of a byte offset.
+pub fn line_col(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let column = text[line_start..offset].chars().count() + 1;
+    (line, column)
+}
+
+/// Keeps a short prefix of the secret so it can be recognised, masks the rest.
+pub fn redact(secret: &str) -> String {
+    let len = secret.chars().count();
+    let keep = (len / 4).min(4);
+    let visible: String = secret.chars().take(keep).collect();
+    format!("{}{}", visible, "*".repeat((len - keep).min(16)))
+}
+
+const MAX_EXCERPT_CHARS: usize = 120;
+
+fn redacted_excerpt(text: &str, start: usize, end: usize) -> String {
+    let line_start = text[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = text[start..]
+        .find('\n')
+        .map(|i| start + i)
+        .unwrap_or(text.len());
+    // A match may span lines (PEM blocks); only its first line is shown
+    let match_end = end.min(line_end);
+
+    let excerpt = format!(
+        "{}{}{}",
+        &text[line_start..start],
+        redact(&text[start..match_end]),
+        &text[match_end..line_end]
+    );
+    let excerpt = excerpt.trim();
+    if excerpt.chars().count() > MAX_EXCERPT_CHARS {
+        let cut: String = excerpt.chars().take(MAX_EXCERPT_CHARS).collect();
+        format!("{}…", cut)
+    } else {
+        excerpt.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_col() {
+        let text = "first\nsecond line\nkey=abc";
+        assert_eq!(line_col(text, 0), (1, 1));
+        assert_eq!(line_col(text, 13), (2, 8));
+        assert_eq!(line_col(text, 22), (3, 5));
+    }
+
+    #[test]
+    fn test_excerpt_masks_secret() {
+        let text = "# config\napi_key = abcdefghijklmnop\n";
+        let start = text.find("abcd").unwrap();
+        let finding = Finding::at_span("r", "Rule", Severity::Block, "f", text, start, start + 16);
+        assert_eq!(finding.location(), "f:2:11");
+        let excerpt = finding.excerpt.unwrap();
+        assert_eq!(excerpt, "api_key = abcd************");
+        assert!(!excerpt.contains("ijklmnop"));
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_position() {
+        let a = "api_key = abcdefghijklmnop";
+        let b = "\n\n  api_key = abcdefghijklmnop";
+        let fa = Finding::at_span("r", "Rule", Severity::Block, "f", a, 10, 26);
+        let fb = Finding::at_span("r", "Rule", Severity::Block, "f", b, 14, 30);
+        let other_file = Finding::at_span("r", "Rule", Severity::Block, "g", a, 10, 26);
+        assert_eq!(fa.fingerprint, fb.fingerprint);
+        assert_ne!(fa.fingerprint, other_file.fingerprint);
+    }
+
+    #[test]
+    fn test_file_level_fingerprint_binds_content() {
+        let finding = || Finding::file_level("binary-file", "Binary", Severity::Block, "a.bin", "");
+        let (mut a, mut same, mut other) = (finding(), finding(), finding());
+        a.bind_content(&content_hash(b"\x7fELF one"));
+        same.bind_content(&content_hash(b"\x7fELF one"));
+        other.bind_content(&content_hash(b"\x7fELF two"));
+        assert_eq!(a.fingerprint, same.fingerprint);
+        assert_ne!(a.fingerprint, other.fingerprint);
+    }
+}
@@ -0,0 +1,261 @@
+use crate::config::{ByteSize, SentinelConfig};
+use crate::sentinel::regex_guard::build_globset;
+use crate::sentinel::{Finding, Severity};
+use anyhow::Result;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use std::fs;
+use std::path::Path;
+
+pub const GITATTRIBUTES_FILE: &str = ".gitattributes";
+
+/// First line of every Git LFS pointer file.
+const LFS_POINTER_HEADER: &[u8] = b"version https://git-lfs.github.com/spec/v1\n";
+/// Pointers are a few lines of text, anything bigger is real content.
+const LFS_POINTER_MAX_SIZE: usize = 1024;
+
+/// Size limits that apply to one path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    pub max_scan_size: ByteSize,
+    pub warn_above: ByteSize,
+    pub block_above: ByteSize,
+}
+
+/// Size limits per path glob, plus the paths `.gitattributes` sends to LFS.
+pub struct LargeFilePolicy {
+    defaults: Limits,
+    overrides: Vec<(GlobSet, Limits)>,
+    lfs: GlobSet,
+}
+
+impl LargeFilePolicy {
+    /// Builds the policy; invalid globs are skipped and reported as errors.
+    pub fn new(config: &SentinelConfig, gitattributes: &Path) -> (Self, Vec<String>) {
+        let mut errors = Vec::new();
+        let defaults = Limits {
+            max_scan_size: config.max_scan_size,
+            warn_above: config.large_file_warn,
+            block_above: config.large_file_block,
+        };
+        let overrides = config
+            .size_limits
+            .iter()
+            .filter_map(|limit| match build_globset(&limit.paths) {
+                Ok(globs) => Some((
+                    globs,
+                    Limits {
+                        max_scan_size: limit.max_scan_size.unwrap_or(defaults.max_scan_size),
+                        warn_above: limit.large_file_warn.unwrap_or(defaults.warn_above),
+                        block_above: limit.large_file_block.unwrap_or(defaults.block_above),
+                    },
+                )),
+                Err(e) => {
+                    errors.push(format!(
+                        "Invalid size_limits paths {:?}: {}",
+                        limit.paths, e
+                    ));
+                    None
+                }
+            })
+            .collect();
+        let lfs = match fs::read_to_string(gitattributes) {
+            Ok(content) => lfs_globs(&content).unwrap_or_else(|e| {
+                errors.push(format!(
+                    "Ignoring LFS patterns in {}: {}",
+                    GITATTRIBUTES_FILE, e
+                ));
+                GlobSet::empty()
+            }),
+            Err(_) => GlobSet::empty(),
+        };
+        (
+            Self {
+                defaults,
+                overrides,
+                lfs,
+            },
+            errors,
+        )
+    }
+
+    pub fn limits(&self, path: &str) -> Limits {
+        self.overrides
+            .iter()
+            .find(|(globs, _)| globs.is_match(path))
+            .map(|(_, limits)| *limits)
+            .unwrap_or(self.defaults)
+    }
+
+    /// True if `.gitattributes` stores `path` in Git LFS.
+    pub fn is_lfs_tracked(&self, path: &str) -> bool {
+        self.lfs.is_match(path)
+    }
+
+    /// A `missing-lfs-pointer` finding if `.gitattributes` sends `path` to
+    /// LFS but the blob `content` is the real file rather than a pointer, e.g.
+    /// because git-lfs is not installed. Only meaningful for blobs, a working
+    /// tree always holds the real content.
+    pub fn check_lfs(&self, path: &str, content: &[u8]) -> Option<Finding> {
+        if !self.is_lfs_tracked(path) || is_lfs_pointer(content) {
+            return None;
+        }
+        let mut finding = Finding::file_level(
+            "missing-lfs-pointer",
+            "Missing LFS pointer",
+            Severity::Warn,
+            path,
+            format!(
+                "Tracked by Git LFS in {} but not stored as an LFS pointer",
+                GITATTRIBUTES_FILE
+            ),
+        );
+        finding.remediation = Some(
+            "Install Git LFS (`git lfs install`) and stage the file again so the pointer is committed instead of the content."
+                .to_string(),
+        );
+        Some(finding)
+    }
+
+    /// A `large-file` finding if `size` is above `limits`, the limits in
+    /// effect for `path`.
+    pub fn check(&self, path: &str, size: u64, limits: Limits) -> Option<Finding> {
+        let exceeded = |limit: ByteSize| limit.0 > 0 && size > limit.0;
+        let (severity, limit) = if exceeded(limits.block_above) {
+            (Severity::Block, limits.block_above)
+        } else if exceeded(limits.warn_above) {
+            (Severity::Warn, limits.warn_above)
+        } else {
+            return None;
+        };
+        let mut finding = Finding::file_level(
+            "large-file",
+            "Large file",
+            severity,
+            path,
+            format!("File is {} (limit {})", ByteSize(size), limit),
+        );
+        finding.remediation = Some(format!(
+            "Store it with Git LFS: `git lfs track \"{}\"`, then stage `{}` and the file again.",
+            lfs_pattern(path),
+            GITATTRIBUTES_FILE
+        ));
+        Some(finding)
+    }
+}
+
+/// True for the small text stub Git LFS stores in place of the real content.
+pub fn is_lfs_pointer(content: &[u8]) -> bool {
+    content.len() <= LFS_POINTER_MAX_SIZE && content.starts_with(LFS_POINTER_HEADER)
+}
+
+/// The pattern to suggest to `git lfs track`: every file with the same
+/// extension, or the path itself when it has none.
+fn lfs_pattern(path: &str) -> String {
+    match Path::new(path).extension() {
+        Some(ext) => format!("*.{}", ext.to_string_lossy()),
+        None => path.to_string(),
+    }
+}
+
+/// Globs for the patterns `.gitattributes` assigns `filter=lfs`. As in
+/// `.gitignore`, a pattern without a slash matches at any depth.
+fn lfs_globs(gitattributes: &str) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for line in gitattributes.lines() {
+        let mut fields = line.split_whitespace();
+        let Some(pattern) = fields.next() else {
+            continue;
+        };
+        if pattern.starts_with('#') || !fields.any(|attr| attr == "filter=lfs") {
+            continue;
+        }
+        let pattern = match pattern.strip_prefix('/') {
+            Some(anchored) => anchored.to_string(),
+            None if pattern.contains('/') => pattern.to_string(),
+            None => format!("**/{}", pattern),
+        };
+        builder.add(GlobBuilder::new(&pattern).literal_separator(true).build()?);
+    }
+    Ok(builder.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, SizeLimit};
+
+    fn policy(gitattributes: &str) -> LargeFilePolicy {
+        let mut config = Config::default().sentinel;
+        config.size_limits = vec![SizeLimit {
+            paths: vec!["assets/**".to_string()],
+            max_scan_size: None,
+            large_file_warn: Some("1MB".parse().unwrap()),
+            large_file_block: Some(ByteSize(0)),
+        }];
+        let path = std::env::temp_dir().join(format!("sgit-gitattributes-{}", std::process::id()));
+        fs::write(&path, gitattributes).unwrap();
+        let (policy, errors) = LargeFilePolicy::new(&config, &path);
+        fs::remove_file(&path).unwrap();
+        assert!(errors.is_empty(), "{:?}", errors);
+        policy
+    }
+
+    fn check(policy: &LargeFilePolicy, path: &str, size: u64) -> Option<Finding> {
+        policy.check(path, size, policy.limits(path))
+    }
+
+    #[test]
+    fn test_byte_size() {
+        assert_eq!("512".parse::<ByteSize>().unwrap(), ByteSize(512));
+        assert_eq!("10 MB".parse::<ByteSize>().unwrap(), ByteSize(10 << 20));
+        assert_eq!("1.5GiB".parse::<ByteSize>().unwrap(), ByteSize(3 << 29));
+        assert!("ten MB".parse::<ByteSize>().is_err());
+        assert!("10 parsecs".parse::<ByteSize>().is_err());
+        assert_eq!(ByteSize(12 << 20).to_string(), "12.0 MB");
+    }
+
+    #[test]
+    fn test_limits_per_glob() {
+        let policy = policy("");
+        let mb = 1 << 20;
+
+        let finding = check(&policy, "assets/intro.mp4", 2 * mb).unwrap();
+        assert_eq!(finding.severity, Severity::Warn);
+        assert!(finding
+            .remediation
+            .unwrap()
+            .contains("git lfs track \"*.mp4\""));
+        // Blocking disabled for assets/, but not elsewhere
+        assert_eq!(
+            check(&policy, "assets/big.bin", 500 * mb).unwrap().severity,
+            Severity::Warn
+        );
+        assert_eq!(
+            check(&policy, "data/big.bin", 500 * mb).unwrap().severity,
+            Severity::Block
+        );
+        assert!(check(&policy, "src/main.rs", 2 * mb).is_none());
+    }
+
+    #[test]
+    fn test_lfs_tracking() {
+        let policy = policy("# binaries\n*.psd filter=lfs diff=lfs merge=lfs -text\n/models/** filter=lfs\n*.txt text\n");
+        assert!(policy.is_lfs_tracked("design/logo.psd"));
+        assert!(policy.is_lfs_tracked("models/v1/weights.bin"));
+        assert!(!policy.is_lfs_tracked("src/models/weights.bin"));
+        assert!(!policy.is_lfs_tracked("notes.txt"));
+
+        let pointer = b"version https://git-lfs.github.com/spec/v1\noid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\nsize 12345\n";
+        assert!(is_lfs_pointer(pointer));
+        assert!(!is_lfs_pointer(b"version 1\n"));
+
+        // Only the pointer is exempt, not the path pattern alone
+        assert!(policy.check_lfs("design/logo.psd", pointer).is_none());
+        let finding = policy
+            .check_lfs("design/logo.psd", b"8BPS\x00\x01")
+            .unwrap();
+        assert_eq!(finding.rule_id, "missing-lfs-pointer");
+        assert_eq!(finding.severity, Severity::Warn);
+        assert!(policy.check_lfs("notes.txt", b"plain text").is_none());
+    }
+}
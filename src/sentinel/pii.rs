@@ -0,0 +1,216 @@
+use crate::config::{PiiConfig, SecretRule};
+use crate::sentinel::Category;
+
+/// Locales with country-specific patterns, for `[sentinel.pii] locales`.
+pub const LOCALES: &[&str] = &["us", "gb", "es"];
+
+const SYNTHETIC_DATA: &str =
+    "Replace it with synthetic data, or keep the real data outside the repository.";
+
+/// The generic PII rules, plus those of the configured locales. Unknown
+/// locales are skipped; `Sentinel::new` reports them.
+pub fn rules(config: &PiiConfig) -> Vec<SecretRule> {
+    let mut rules = vec![
+        SecretRule::new(
+            "pii-email",
+            "Email address",
+            r"\b([A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,})\b",
+            &["@"],
+            "Use an address under a reserved domain such as example.com instead.",
+        )
+        .validated_by("email"),
+        SecretRule::new(
+            "pii-phone",
+            "International phone number",
+            r"(?:^|[^\w+])(\+[1-9]\d{0,2}(?:[ .-]?\(?\d{1,4}\)?){2,6})",
+            &["+"],
+            SYNTHETIC_DATA,
+        )
+        .validated_by("phone"),
+        SecretRule::new(
+            "pii-iban",
+            "IBAN",
+            r"\b([A-Z]{2}\d{2}(?:[ ]?[A-Z0-9]{4}){2,7}(?:[ ]?[A-Z0-9]{1,3})?)\b",
+            &[],
+            SYNTHETIC_DATA,
+        )
+        .validated_by("iban"),
+        SecretRule::new(
+            "pii-credit-card",
+            "Credit card number",
+            r"\b((?:4\d{3}|5[1-5]\d{2}|2[2-7]\d{2}|6(?:011|5\d{2}))(?:[ -]?\d{4}){3}|3[47]\d{2}[ -]?\d{6}[ -]?\d{5})\b",
+            &[],
+            "Use the test card numbers your payment provider documents instead.",
+        )
+        .validated_by("luhn"),
+    ];
+    for locale in &config.locales {
+        rules.extend(locale_rules(locale));
+    }
+    rules
+        .into_iter()
+        .map(|r| r.in_category(Category::Pii).with_severity(config.severity))
+        .collect()
+}
+
+fn locale_rules(locale: &str) -> Vec<SecretRule> {
+    match locale {
+        "us" => vec![
+            SecretRule::new(
+                "pii-us-ssn",
+                "US Social Security number",
+                r"\b(\d{3}-\d{2}-\d{4})\b",
+                &["-"],
+                SYNTHETIC_DATA,
+            )
+            .validated_by("us-ssn"),
+            SecretRule::new(
+                "pii-us-phone",
+                "US phone number",
+                r"(?:^|[^\w+])(\(?[2-9]\d{2}\)?[ .-]?[2-9]\d{2}[ .-]\d{4})\b",
+                &[],
+                "Use a fictional 555-01xx number instead.",
+            )
+            .validated_by("phone"),
+        ],
+        "gb" => vec![
+            SecretRule::new(
+                "pii-gb-nino",
+                "UK National Insurance number",
+                r"\b([A-CEGHJ-PR-TW-Z][A-CEGHJ-NPR-TW-Z] ?\d{2} ?\d{2} ?\d{2} ?[A-D])\b",
+                &[],
+                SYNTHETIC_DATA,
+            )
+            .validated_by("gb-nino"),
+            SecretRule::new(
+                "pii-gb-phone",
+                "UK mobile number",
+                r"(?:^|[^\w+])(07\d{3} ?\d{6})\b",
+                &["07"],
+                "Use a number from the Ofcom drama range (07700 900000 to 900999) instead.",
+            )
+            .validated_by("phone"),
+        ],
+        "es" => vec![
+            SecretRule::new(
+                "pii-es-dni",
+                "Spanish DNI",
+                r"\b(\d{8}-?[A-HJ-NP-TV-Z])\b",
+                &[],
+                SYNTHETIC_DATA,
+            )
+            .validated_by("es-dni"),
+            SecretRule::new(
+                "pii-es-nie",
+                "Spanish NIE",
+                r"\b([XYZ]-?\d{7}-?[A-HJ-NP-TV-Z])\b",
+                &[],
+                SYNTHETIC_DATA,
+            )
+            .validated_by("es-dni"),
+            SecretRule::new(
+                "pii-es-phone",
+                "Spanish mobile number",
+                r"(?:^|[^\w+])([67]\d{2} ?\d{3} ?\d{3}|[67]\d{2} \d{2} \d{2} \d{2})\b",
+                &[],
+                SYNTHETIC_DATA,
+            )
+            .validated_by("phone"),
+        ],
+        _ => vec![],
+    }
+}
+
+/// Ids of every PII rule in every locale, so they can be referenced in
+/// `disabled_rules` or overrides before the family is enabled.
+pub fn all_ids() -> Vec<String> {
+    let config = PiiConfig {
+        locales: LOCALES.iter().map(|l| l.to_string()).collect(),
+        ..PiiConfig::default()
+    };
+    rules(&config).into_iter().map(|r| r.id).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sentinel::regex_guard::{check_rules, compile_rules};
+    use crate::sentinel::Severity;
+    use std::collections::HashSet;
+
+    /// (rule id, text that must match, text that must not).
+    fn samples() -> Vec<(&'static str, &'static str, &'static str)> {
+        vec![
+            (
+                "pii-email",
+                "contact: jane.doe@acme.io",
+                "import '@angular/core'",
+            ),
+            ("pii-phone", "tel: +44 20 7946 0958", "x = a+1; y = 2 + 3"),
+            (
+                "pii-iban",
+                "iban = \"DE89 3704 0044 0532 0130 00\"",
+                "sha = \"DE89\"",
+            ),
+            (
+                "pii-credit-card",
+                "card: 4539 1488 0343 6467",
+                "order: 4539 1488 03",
+            ),
+            ("pii-us-ssn", "ssn: 536-22-8137", "date: 2024-01-15"),
+            ("pii-us-phone", "call (415) 555-2671", "version 415.5552671"),
+            ("pii-gb-nino", "NI: AB 12 34 56 C", "ref: AB12"),
+            ("pii-gb-phone", "mobile 07700 900123", "build 0770"),
+            ("pii-es-dni", "DNI 12345678Z", "id 1234567Z"),
+            ("pii-es-nie", "NIE X-1234567-L", "X12L"),
+            ("pii-es-phone", "móvil: 612 34 56 78", "cp 28013"),
+        ]
+    }
+
+    fn all_locales() -> PiiConfig {
+        PiiConfig {
+            enabled: true,
+            severity: Severity::Block,
+            locales: LOCALES.iter().map(|l| l.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_every_rule_detects_its_sample() {
+        let rules = rules(&all_locales());
+        assert!(rules
+            .iter()
+            .all(|r| r.category == Category::Pii && r.severity == Severity::Block));
+        let compiled = compile_rules(&rules);
+        assert!(compiled.errors.is_empty(), "{:?}", compiled.errors);
+
+        let tested: HashSet<&str> = samples().iter().map(|(id, _, _)| *id).collect();
+        for rule in &rules {
+            assert!(
+                tested.contains(rule.id.as_str()),
+                "no samples for {}",
+                rule.id
+            );
+        }
+        for (id, positive, negative) in samples() {
+            let hit = |text: &str| {
+                check_rules(text, "fixtures.txt", &compiled)
+                    .iter()
+                    .any(|m| m.rule.id == id)
+            };
+            assert!(hit(positive), "{} should match {:?}", id, positive);
+            assert!(!hit(negative), "{} should not match {:?}", id, negative);
+        }
+    }
+
+    #[test]
+    fn test_locales() {
+        let mut config = PiiConfig::default();
+        assert_eq!(rules(&config).len(), 4);
+        config.locales = vec!["es".to_string(), "xx".to_string()];
+        let ids: Vec<String> = rules(&config).into_iter().map(|r| r.id).collect();
+        assert!(ids.contains(&"pii-es-dni".to_string()));
+        assert!(!ids.contains(&"pii-us-ssn".to_string()));
+        assert_eq!(all_ids().len(), 11);
+    }
+}
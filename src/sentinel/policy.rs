@@ -0,0 +1,236 @@
+use crate::config::PolicyOverride;
+use crate::sentinel::large_file::Limits;
+use crate::sentinel::regex_guard::build_globset;
+use crate::sentinel::validators::Validation;
+use crate::sentinel::{Finding, Severity};
+use globset::GlobSet;
+use std::collections::BTreeMap;
+
+/// Key of `severities` that applies to every rule.
+const ANY_RULE: &str = "*";
+
+/// The compiled `[[sentinel.overrides]]` entries.
+pub struct Overrides {
+    entries: Vec<(GlobSet, PolicyOverride)>,
+}
+
+impl Overrides {
+    /// Entries with invalid globs are skipped and reported as errors.
+    pub fn new(overrides: &[PolicyOverride]) -> (Self, Vec<String>) {
+        let mut errors = Vec::new();
+        let entries = overrides
+            .iter()
+            .filter_map(|o| match build_globset(&o.paths) {
+                Ok(globs) => Some((globs, o.clone())),
+                Err(e) => {
+                    errors.push(format!("Invalid overrides paths {:?}: {}", o.paths, e));
+                    None
+                }
+            })
+            .collect();
+        (Self { entries }, errors)
+    }
+
+    /// Layers every matching entry, in order, over the base settings.
+    pub fn resolve(&self, path: &str, binary_extensions: &[String], limits: Limits) -> Policy {
+        let mut policy = Policy {
+            matched: vec![],
+            disabled_rules: vec![],
+            severities: BTreeMap::new(),
+            binary_extensions: binary_extensions.to_vec(),
+            limits,
+        };
+        for (globs, entry) in &self.entries {
+            if !globs.is_match(path) {
+                continue;
+            }
+            policy.matched.push(entry.paths.join(", "));
+            policy
+                .disabled_rules
+                .extend(entry.disabled_rules.iter().cloned());
+            policy.severities.extend(entry.severities.clone());
+            policy.binary_extensions.extend(
+                entry
+                    .binary_extensions
+                    .iter()
+                    .map(|e| e.trim_start_matches('.').to_lowercase()),
+            );
+            if let Some(size) = entry.max_scan_size {
+                policy.limits.max_scan_size = size;
+            }
+            if let Some(size) = entry.large_file_warn {
+                policy.limits.warn_above = size;
+            }
+            if let Some(size) = entry.large_file_block {
+                policy.limits.block_above = size;
+            }
+        }
+        policy.disabled_rules.sort();
+        policy.disabled_rules.dedup();
+        policy.binary_extensions.sort();
+        policy.binary_extensions.dedup();
+        policy
+    }
+
+    /// Every rule id the entries refer to, to catch typos.
+    pub fn rule_ids(&self) -> impl Iterator<Item = &String> {
+        self.entries.iter().flat_map(|(_, entry)| {
+            entry
+                .disabled_rules
+                .iter()
+                .chain(entry.severities.keys().filter(|id| *id != ANY_RULE))
+        })
+    }
+}
+
+/// The Sentinel settings in effect for one path.
+#[derive(Debug, Clone)]
+pub struct Policy {
+    /// Paths of the override entries that matched, in order.
+    pub matched: Vec<String>,
+    pub disabled_rules: Vec<String>,
+    pub severities: BTreeMap<String, Severity>,
+    pub binary_extensions: Vec<String>,
+    pub limits: Limits,
+}
+
+impl Policy {
+    pub fn is_disabled(&self, rule_id: &str) -> bool {
+        self.disabled_rules.iter().any(|id| id == rule_id)
+    }
+
+    /// An exact rule id takes precedence over `"*"`.
+    pub fn severity(&self, rule_id: &str) -> Option<Severity> {
+        self.severities
+            .get(rule_id)
+            .or_else(|| self.severities.get(ANY_RULE))
+            .copied()
+    }
+
+    /// Drops disabled findings and applies severity changes. Likely
+    /// placeholders keep their downgrade.
+    pub fn apply(&self, findings: &mut Vec<Finding>) {
+        if self.matched.is_empty() {
+            return;
+        }
+        findings.retain(|f| !self.is_disabled(&f.rule_id));
+        for finding in findings {
+            if finding.validation == Some(Validation::LikelyPlaceholder) {
+                continue;
+            }
+            if let Some(severity) = self.severity(&finding.rule_id) {
+                finding.severity = severity;
+            }
+        }
+    }
+
+    /// One line per setting that differs from the global configuration.
+    pub fn describe(&self, base_extensions: &[String]) -> Vec<String> {
+        let mut lines = Vec::new();
+        if !self.disabled_rules.is_empty() {
+            lines.push(format!("disabled: {}", self.disabled_rules.join(", ")));
+        }
+        if !self.severities.is_empty() {
+            let severities: Vec<String> = self
+                .severities
+                .iter()
+                .map(|(id, severity)| format!("{}={}", id, severity))
+                .collect();
+            lines.push(format!("severity: {}", severities.join(", ")));
+        }
+        let extra: Vec<&str> = self
+            .binary_extensions
+            .iter()
+            .filter(|e| !base_extensions.contains(e))
+            .map(String::as_str)
+            .collect();
+        if !extra.is_empty() {
+            lines.push(format!("binary: +{}", extra.join(", +")));
+        }
+        lines.push(format!(
+            "max scan {}, large file warn {} / block {}",
+            self.limits.max_scan_size, self.limits.warn_above, self.limits.block_above
+        ));
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ByteSize, Config};
+
+    fn entry(paths: &[&str]) -> PolicyOverride {
+        PolicyOverride {
+            paths: paths.iter().map(|p| p.to_string()).collect(),
+            disabled_rules: vec![],
+            severities: BTreeMap::new(),
+            binary_extensions: vec![],
+            max_scan_size: None,
+            large_file_warn: None,
+            large_file_block: None,
+        }
+    }
+
+    fn limits() -> Limits {
+        let config = Config::default().sentinel;
+        Limits {
+            max_scan_size: config.max_scan_size,
+            warn_above: config.large_file_warn,
+            block_above: config.large_file_block,
+        }
+    }
+
+    #[test]
+    fn test_layered_entries() {
+        let mut fixtures = entry(&["tests/fixtures/**"]);
+        fixtures.disabled_rules = vec!["aws-access-key-id".to_string()];
+        fixtures
+            .severities
+            .insert(ANY_RULE.to_string(), Severity::Info);
+        let mut strict = entry(&["**/*.tf", "tests/fixtures/strict/**"]);
+        strict
+            .severities
+            .insert("high-entropy".to_string(), Severity::Block);
+        strict.binary_extensions = vec![".TFSTATE".to_string()];
+        strict.max_scan_size = Some(ByteSize(1024));
+        let (overrides, errors) = Overrides::new(&[fixtures, strict]);
+        assert!(errors.is_empty());
+
+        let base = vec!["exe".to_string()];
+        let policy = overrides.resolve("tests/fixtures/strict/main.tf", &base, limits());
+        assert_eq!(policy.matched.len(), 2);
+        assert!(policy.is_disabled("aws-access-key-id"));
+        assert_eq!(policy.severity("high-entropy"), Some(Severity::Block));
+        assert_eq!(policy.severity("jwt"), Some(Severity::Info));
+        assert_eq!(policy.binary_extensions, ["exe", "tfstate"]);
+        assert_eq!(policy.limits.max_scan_size, ByteSize(1024));
+
+        let policy = overrides.resolve("src/main.rs", &base, limits());
+        assert!(policy.matched.is_empty());
+        assert_eq!(policy.severity("jwt"), None);
+        assert_eq!(policy.limits, limits());
+    }
+
+    #[test]
+    fn test_apply_to_findings() {
+        let mut assets = entry(&["assets/**"]);
+        assets.disabled_rules = vec!["binary-file".to_string()];
+        assets
+            .severities
+            .insert("high-entropy".to_string(), Severity::Block);
+        let (overrides, _) = Overrides::new(&[assets]);
+        let policy = overrides.resolve("assets/logo.svg", &[], limits());
+
+        let mut placeholder = Finding::file_level("high-entropy", "", Severity::Info, "", "");
+        placeholder.validation = Some(Validation::LikelyPlaceholder);
+        let mut findings = vec![
+            Finding::file_level("binary-file", "", Severity::Block, "", ""),
+            Finding::file_level("high-entropy", "", Severity::Warn, "", ""),
+            placeholder,
+        ];
+        policy.apply(&mut findings);
+        let severities: Vec<_> = findings.iter().map(|f| f.severity).collect();
+        assert_eq!(severities, [Severity::Block, Severity::Info]);
+    }
+}
@@ -0,0 +1,325 @@
+use crate::sentinel::finding::content_hash;
+use crate::sentinel::Finding;
+use anyhow::{anyhow, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+lazy_static! {
+    /// The key of an assignment ending right before the secret, e.g.
+    /// `aws_secret_access_key = "` or `"apiKey": "`.
+    static ref KEY_RE: Regex =
+        Regex::new(r#"([A-Za-z_][A-Za-z0-9_.\-]*)["']?\s*[:=]\s*["']?$"#).unwrap();
+}
+
+pub const ENV_EXAMPLE_FILE: &str = ".env.example";
+pub const GITIGNORE_FILE: &str = ".gitignore";
+
+/// What the secret is replaced with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Replacement {
+    /// `${NAME}`, resolved from the environment at runtime.
+    EnvVar,
+    /// `<NAME>`, for docs and samples that only need the shape.
+    Placeholder,
+}
+
+impl Replacement {
+    pub fn toggle(self) -> Self {
+        match self {
+            Replacement::EnvVar => Replacement::Placeholder,
+            Replacement::Placeholder => Replacement::EnvVar,
+        }
+    }
+
+    pub fn render(self, name: &str) -> String {
+        match self {
+            Replacement::EnvVar => format!("${{{}}}", name),
+            Replacement::Placeholder => format!("<{}>", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    pub replacement: Replacement,
+    /// Append `NAME=` to `.env.example`.
+    pub env_example: bool,
+    /// Add the file itself to `.gitignore`.
+    pub gitignore: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            replacement: Replacement::EnvVar,
+            env_example: true,
+            gitignore: false,
+        }
+    }
+}
+
+/// New content for one file. `old` is empty for files that do not exist yet.
+#[derive(Debug, Clone)]
+pub struct FileEdit {
+    pub path: PathBuf,
+    pub old: String,
+    pub new: String,
+    /// Hash of the file when the edit was planned, `None` if it did not exist.
+    pub base: Option<String>,
+}
+
+/// Every change a remediation would write, for preview before applying.
+#[derive(Debug, Clone)]
+pub struct Plan {
+    pub var_name: String,
+    pub edits: Vec<FileEdit>,
+}
+
+/// Builds the edits that replace `finding` in `content` (the current
+/// content of `path`, relative to `root`).
+pub fn plan(
+    root: &Path,
+    path: &str,
+    content: &str,
+    finding: &Finding,
+    options: &Options,
+) -> Result<Plan> {
+    let span = finding
+        .span
+        .filter(|s| {
+            s.start <= s.end
+                && s.end <= content.len()
+                && content.is_char_boundary(s.start)
+                && content.is_char_boundary(s.end)
+        })
+        .ok_or_else(|| anyhow!("{} has no location in the current file", finding.rule_id))?;
+    // A finding from an older version of the file must not overwrite other text
+    if !finding.is_match(&content[span.start..span.end]) {
+        return Err(anyhow!(
+            "{} no longer matches the current file, scan it again",
+            finding.rule_id
+        ));
+    }
+
+    let var_name = env_var_name(content, finding);
+    let mut edits = vec![FileEdit {
+        path: root.join(path),
+        old: content.to_string(),
+        new: format!(
+            "{}{}{}",
+            &content[..span.start],
+            options.replacement.render(&var_name),
+            &content[span.end..]
+        ),
+        base: Some(content_hash(content.as_bytes())),
+    }];
+
+    if options.env_example {
+        edits.push(append_line(
+            &root.join(ENV_EXAMPLE_FILE),
+            &format!("{}=", var_name),
+            |line| line.split('=').next() == Some(var_name.as_str()),
+        )?);
+    }
+    if options.gitignore {
+        let entry = format!("/{}", path);
+        edits.push(append_line(&root.join(GITIGNORE_FILE), &entry, |line| {
+            line == entry || line == path
+        })?);
+    }
+
+    edits.retain(|e| e.old != e.new);
+    Ok(Plan { var_name, edits })
+}
+
+/// Writes every edit of the plan. Nothing is written if any file changed
+/// since the plan was made, since the edits would overwrite that change.
+pub fn apply(plan: &Plan) -> Result<()> {
+    for edit in &plan.edits {
+        let current = match fs::read(&edit.path) {
+            Ok(content) => Some(content_hash(&content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        if current != edit.base {
+            return Err(anyhow!(
+                "{} changed since the fix was previewed",
+                edit.path.display()
+            ));
+        }
+    }
+    for edit in &plan.edits {
+        fs::write(&edit.path, &edit.new)?;
+    }
+    Ok(())
+}
+
+/// Upper snake case name for the secret: the key it is assigned to, or
+/// the rule id when there is no assignment.
+pub fn env_var_name(content: &str, finding: &Finding) -> String {
+    let key = finding.span.and_then(|span| {
+        let line_start = content[..span.start]
+            .rfind('\n')
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        KEY_RE
+            .captures(&content[line_start..span.start])
+            .map(|caps| caps[1].to_string())
+    });
+    let name = key.unwrap_or_else(|| finding.rule_id.clone());
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Appends `line` to the file unless an existing line satisfies `present`.
+fn append_line(path: &Path, line: &str, present: impl Fn(&str) -> bool) -> Result<FileEdit> {
+    let (old, base) = if path.exists() {
+        let old = fs::read_to_string(path)?;
+        let base = content_hash(old.as_bytes());
+        (old, Some(base))
+    } else {
+        (String::new(), None)
+    };
+    let mut new = old.clone();
+    if !old.lines().map(str::trim).any(present) {
+        if !new.is_empty() && !new.ends_with('\n') {
+            new.push('\n');
+        }
+        new.push_str(line);
+        new.push('\n');
+    }
+    Ok(FileEdit {
+        path: path.to_path_buf(),
+        old,
+        new,
+        base,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sentinel::Severity;
+
+    fn finding(content: &str, secret: &str) -> Finding {
+        let start = content.find(secret).unwrap();
+        Finding::at_span(
+            "github-token",
+            "GitHub token",
+            Severity::Block,
+            "config.yml",
+            content,
+            start,
+            start + secret.len(),
+        )
+    }
+
+    #[test]
+    fn test_env_var_name() {
+        let content = "deploy:\n  apiKey: \"s3cr3t-value\"\n";
+        assert_eq!(
+            env_var_name(content, &finding(content, "s3cr3t-value")),
+            "APIKEY"
+        );
+
+        let content = "curl -H 'Authorization: token s3cr3t-value'";
+        assert_eq!(
+            env_var_name(content, &finding(content, "s3cr3t-value")),
+            "GITHUB_TOKEN"
+        );
+    }
+
+    #[test]
+    fn test_plan_and_apply() {
+        let root = std::env::temp_dir().join(format!("sgit-remediation-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join(GITIGNORE_FILE), "target").unwrap();
+
+        let content = "aws_secret_access_key = 's3cr3t-value'\n";
+        fs::write(root.join("config.yml"), content).unwrap();
+        let options = Options {
+            replacement: Replacement::EnvVar,
+            env_example: true,
+            gitignore: true,
+        };
+        let plan = plan(
+            &root,
+            "config.yml",
+            content,
+            &finding(content, "s3cr3t-value"),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(plan.var_name, "AWS_SECRET_ACCESS_KEY");
+        assert_eq!(plan.edits.len(), 3);
+        assert_eq!(
+            plan.edits[0].new,
+            "aws_secret_access_key = '${AWS_SECRET_ACCESS_KEY}'\n"
+        );
+        assert_eq!(plan.edits[1].new, "AWS_SECRET_ACCESS_KEY=\n");
+        assert_eq!(plan.edits[2].new, "target\n/config.yml\n");
+
+        apply(&plan).unwrap();
+        // Entries that are already present are not added twice
+        let again = super::plan(
+            &root,
+            "config.yml",
+            content,
+            &finding(content, "s3cr3t-value"),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(again.edits.len(), 1);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_plan_refuses_stale_finding() {
+        let root = Path::new("unused");
+        let old = "api_key = 's3cr3t-value'\n";
+        let stale = finding(old, "s3cr3t-value");
+
+        // Same offsets, different text
+        let content = "api_key = 'other-value!'\n";
+        assert!(plan(root, "config.yml", content, &stale, &Options::default()).is_err());
+        // The span would end inside a multi-byte character
+        let content = "api_key = 's3cr3t-valuü'\n";
+        assert!(plan(root, "config.yml", content, &stale, &Options::default()).is_err());
+    }
+
+    #[test]
+    fn test_apply_refuses_changed_file() {
+        let root =
+            std::env::temp_dir().join(format!("sgit-remediation-stale-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let content = "api_key = 's3cr3t-value'\n";
+        fs::write(root.join("config.yml"), content).unwrap();
+        let plan = plan(
+            &root,
+            "config.yml",
+            content,
+            &finding(content, "s3cr3t-value"),
+            &Options::default(),
+        )
+        .unwrap();
+
+        // Edited in another window between preview and apply
+        let edited = "api_key = 's3cr3t-value'\nregion = 'eu-west-1'\n";
+        fs::write(root.join("config.yml"), edited).unwrap();
+        assert!(apply(&plan).is_err());
+        assert_eq!(fs::read_to_string(root.join("config.yml")).unwrap(), edited);
+        assert!(!root.join(ENV_EXAMPLE_FILE).exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
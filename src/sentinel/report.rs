@@ -0,0 +1,302 @@
+use crate::sentinel::finding::Suppression;
+use crate::sentinel::rule_pack;
+use crate::sentinel::{Finding, Severity};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+/// Findings in a single file.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileReport {
+    pub path: String,
+    pub findings: Vec<Finding>,
+}
+
+/// Result of a headless Sentinel run over a set of files.
+#[derive(Debug, Default, Serialize)]
+pub struct ScanReport {
+    pub scanned_files: usize,
+    /// Only set by history scans.
+    pub scanned_commits: usize,
+    pub files: Vec<FileReport>,
+}
+
+impl ScanReport {
+    pub fn add(&mut self, path: &str, findings: Vec<Finding>) {
+        self.scanned_files += 1;
+        if !findings.is_empty() {
+            self.files.push(FileReport {
+                path: path.to_string(),
+                findings,
+            });
+        }
+    }
+
+    /// Adds the findings of one commit, grouped by file.
+    pub fn add_commit(&mut self, findings: Vec<Finding>) {
+        self.scanned_commits += 1;
+        let first = self.files.len();
+        for finding in findings {
+            match self.files[first..]
+                .iter_mut()
+                .find(|f| f.path == finding.path)
+            {
+                Some(file) => file.findings.push(finding),
+                None => self.files.push(FileReport {
+                    path: finding.path.clone(),
+                    findings: vec![finding],
+                }),
+            }
+        }
+    }
+
+    pub fn findings(&self) -> impl Iterator<Item = &Finding> {
+        self.files.iter().flat_map(|f| &f.findings)
+    }
+
+    /// Findings that are not suppressed.
+    pub fn active(&self) -> impl Iterator<Item = &Finding> {
+        self.findings().filter(|f| !f.is_suppressed())
+    }
+
+    pub fn finding_count(&self) -> usize {
+        self.active().count()
+    }
+
+    pub fn suppressed_count(&self) -> usize {
+        self.findings().filter(|f| f.is_suppressed()).count()
+    }
+
+    /// True if any unsuppressed finding is at or above `fail_on`.
+    pub fn is_blocking(&self, fail_on: Severity) -> bool {
+        self.active().any(|f| f.severity >= fail_on)
+    }
+}
+
+pub fn render_text(report: &ScanReport) -> String {
+    let mut out = String::new();
+    // Inline-allowed findings stay listed for auditability, baselined ones don't
+    let listed = report
+        .findings()
+        .filter(|f| !matches!(f.suppressed, Some(Suppression::Baseline)));
+    for finding in listed {
+        if let Some(suppression) = &finding.suppressed {
+            out.push_str(&format!("{} (suppressed: {})\n", finding, suppression));
+            continue;
+        }
+        out.push_str(&format!("{}\n", finding));
+        if let Some(commit) = &finding.commit {
+            out.push_str(&format!(
+                "    commit {} by {} on {}\n",
+                commit.id, commit.author, commit.date
+            ));
+        }
+        if let Some(excerpt) = &finding.excerpt {
+            out.push_str(&format!("    {}\n", excerpt));
+        }
+        if let Some(remediation) = &finding.remediation {
+            out.push_str(&format!("    fix: {}\n", remediation));
+        }
+    }
+    let affected = report
+        .files
+        .iter()
+        .filter(|f| f.findings.iter().any(|f| !f.is_suppressed()))
+        .count();
+    out.push_str(&format!(
+        "{} finding(s) in {} file(s), ",
+        report.finding_count(),
+        affected
+    ));
+    if report.scanned_commits > 0 {
+        out.push_str(&format!("{} commit(s) scanned", report.scanned_commits));
+    } else {
+        out.push_str(&format!("{} file(s) scanned", report.scanned_files));
+    }
+    let suppressed = report.suppressed_count();
+    if suppressed > 0 {
+        out.push_str(&format!(", {} suppressed", suppressed));
+    }
+    out.push('\n');
+    out
+}
+
+pub fn render_json(report: &ScanReport, fail_on: Severity) -> String {
+    let value = json!({
+        "tool": "sgit",
+        "version": env!("CARGO_PKG_VERSION"),
+        "scanned_files": report.scanned_files,
+        "scanned_commits": report.scanned_commits,
+        "finding_count": report.finding_count(),
+        "suppressed_count": report.suppressed_count(),
+        "fail_on": fail_on,
+        "rule_pack_version": rule_pack::RULE_PACK_VERSION,
+        "blocking": report.is_blocking(fail_on),
+        "findings": report.findings().collect::<Vec<_>>(),
+    });
+    serde_json::to_string_pretty(&value).unwrap_or_default()
+}
+
+/// SARIF 2.1.0, as consumed by GitHub code scanning and most editors.
+pub fn render_sarif(report: &ScanReport) -> String {
+    // One rule descriptor per distinct rule id, in a stable order
+    let rules: BTreeMap<&str, &Finding> =
+        report.findings().map(|f| (f.rule_id.as_str(), f)).collect();
+    let rule_descriptors: Vec<serde_json::Value> = rules
+        .values()
+        .map(|f| {
+            let mut rule = json!({
+                "id": f.rule_id,
+                "name": f.name,
+                "shortDescription": { "text": f.name },
+                "defaultConfiguration": { "level": sarif_level(f.severity) },
+            });
+            if let Some(remediation) = &f.remediation {
+                rule["help"] = json!({ "text": remediation });
+            }
+            rule
+        })
+        .collect();
+
+    let results: Vec<serde_json::Value> = report
+        .findings()
+        .map(|f| {
+            let mut location = json!({
+                "artifactLocation": { "uri": f.path }
+            });
+            if let (Some(line), Some(column)) = (f.line, f.column) {
+                location["region"] = json!({
+                    "startLine": line,
+                    "startColumn": column,
+                });
+                if let Some(excerpt) = &f.excerpt {
+                    location["region"]["snippet"] = json!({ "text": excerpt });
+                }
+            }
+            let mut result = json!({
+                "ruleId": f.rule_id,
+                "level": sarif_level(f.severity),
+                "message": { "text": f.message },
+                "locations": [{ "physicalLocation": location }],
+                "partialFingerprints": { "sgit/v1": f.fingerprint },
+            });
+            if let Some(commit) = &f.commit {
+                result["properties"] = json!({
+                    "commit": commit.id,
+                    "author": commit.author,
+                    "date": commit.date,
+                });
+            }
+            result["properties"]["category"] = json!(f.category);
+            if let Some(validation) = f.validation {
+                result["properties"]["validation"] = json!(validation);
+            }
+            match &f.suppressed {
+                // Baselines live outside the source file
+                Some(Suppression::Baseline) => {
+                    result["suppressions"] = json!([{ "kind": "external" }]);
+                }
+                Some(Suppression::Inline { reason }) => {
+                    result["suppressions"] = json!([{
+                        "kind": "inSource",
+                        "justification": reason.clone().unwrap_or_default(),
+                    }]);
+                }
+                None => {}
+            }
+            result
+        })
+        .collect();
+
+    let value = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "sgit",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "properties": { "rulePackVersion": rule_pack::RULE_PACK_VERSION },
+                    "rules": rule_descriptors,
+                }
+            },
+            "results": results,
+        }]
+    });
+    serde_json::to_string_pretty(&value).unwrap_or_default()
+}
+
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Block => "error",
+        Severity::Warn => "warning",
+        Severity::Info => "note",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> ScanReport {
+        let text = "token = hunter2hunter2";
+        let mut report = ScanReport::default();
+        report.add("clean.rs", vec![]);
+        report.add(
+            "config.env",
+            vec![Finding::at_span(
+                "generic-token",
+                "Generic token",
+                Severity::Warn,
+                "config.env",
+                text,
+                8,
+                text.len(),
+            )],
+        );
+        report
+    }
+
+    #[test]
+    fn test_clean_files_are_counted_but_not_listed() {
+        let report = sample();
+        assert_eq!(report.scanned_files, 2);
+        assert_eq!(report.files.len(), 1);
+        assert!(report.is_blocking(Severity::Warn));
+        assert!(!report.is_blocking(Severity::Block));
+    }
+
+    #[test]
+    fn test_inline_suppression_is_listed_not_counted() {
+        let mut report = sample();
+        report.files[0].findings[0].suppressed = Some(Suppression::Inline {
+            reason: Some("fixture".to_string()),
+        });
+        assert!(!report.is_blocking(Severity::Info));
+        let text = render_text(&report);
+        assert!(text.contains("(suppressed: sgit:allow, fixture)"));
+        assert!(text.contains("0 finding(s) in 0 file(s), 2 file(s) scanned, 1 suppressed"));
+
+        let sarif: serde_json::Value = serde_json::from_str(&render_sarif(&report)).unwrap();
+        let suppression = &sarif["runs"][0]["results"][0]["suppressions"][0];
+        assert_eq!(suppression["kind"], "inSource");
+        assert_eq!(suppression["justification"], "fixture");
+    }
+
+    #[test]
+    fn test_sarif_structure() {
+        let sarif: serde_json::Value = serde_json::from_str(&render_sarif(&sample())).unwrap();
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(
+            sarif["runs"][0]["tool"]["driver"]["rules"][0]["id"],
+            "generic-token"
+        );
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["ruleId"], "generic-token");
+        assert_eq!(results[0]["level"], "warning");
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "config.env");
+        assert_eq!(location["region"]["startColumn"], 9);
+    }
+}
@@ -1,6 +1,8 @@
+use crate::sentinel::Finding;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
+    text::Line,
     widgets::{Block, Borders, Paragraph, Wrap},
     Frame,
 };
@@ -23,6 +25,10 @@ pub struct CommitWizardState<'a> {
     pub desc_input: TextArea<'a>,
     pub body_input: TextArea<'a>,
     pub footer_input: TextArea<'a>,
+    /// Sentinel findings in the formatted message, set on reaching Confirmation.
+    pub findings: Vec<Finding>,
+    /// True if `findings` block the commit.
+    pub blocked: bool,
 }
 
 impl<'a> Default for CommitWizardState<'a> {
@@ -58,6 +64,8 @@ impl<'a> Default for CommitWizardState<'a> {
             desc_input,
             body_input,
             footer_input,
+            findings: vec![],
+            blocked: false,
         }
     }
 }
@@ -80,6 +88,14 @@ impl<'a> CommitWizardState<'a> {
                 // Let parent handle close
                 true
             }
+            // Back to the previous step from an empty field, to fix a blocked message
+            Input {
+                key: Key::Backspace,
+                ..
+            } if self.current_input_is_empty() => {
+                self.previous_step();
+                true
+            }
             _ => {
                 match self.step {
                     WizardStep::Type => {
@@ -115,6 +131,29 @@ impl<'a> CommitWizardState<'a> {
         };
     }
 
+    pub fn previous_step(&mut self) {
+        self.step = match self.step {
+            WizardStep::Type => WizardStep::Type,
+            WizardStep::Scope => WizardStep::Type,
+            WizardStep::Description => WizardStep::Scope,
+            WizardStep::Body => WizardStep::Description,
+            WizardStep::Footer => WizardStep::Body,
+            WizardStep::Confirmation => WizardStep::Footer,
+        };
+    }
+
+    fn current_input_is_empty(&self) -> bool {
+        let input = match self.step {
+            WizardStep::Type => &self.type_input,
+            WizardStep::Scope => &self.scope_input,
+            WizardStep::Description => &self.desc_input,
+            WizardStep::Body => &self.body_input,
+            WizardStep::Footer => &self.footer_input,
+            WizardStep::Confirmation => return true,
+        };
+        input.is_empty()
+    }
+
     pub fn format_commit_message(&self) -> String {
         let type_ = self.type_input.lines().first().cloned().unwrap_or_default();
         let scope = self
@@ -208,11 +247,69 @@ pub fn render(f: &mut Frame, area: Rect, state: &mut CommitWizardState) {
         WizardStep::Footer => {
             "Enter any footer information, such as 'BREAKING CHANGE: ...' or 'Closes #123'."
         }
-        WizardStep::Confirmation => "Press Enter to COMMIT. Press Esc to cancel.",
+        WizardStep::Confirmation => {
+            "Press Enter to COMMIT. Press Backspace to go back, Esc to cancel."
+        }
     };
 
+    if state.step == WizardStep::Confirmation && !state.findings.is_empty() {
+        render_findings(f, chunks[2], state);
+        return;
+    }
+
     let help = Paragraph::new(help_text)
         .style(Style::default().fg(Color::Gray))
         .block(Block::default().borders(Borders::ALL).title(" Help "));
     f.render_widget(help, chunks[2]);
 }
+
+/// What Sentinel found in the message, in place of the help text.
+fn render_findings(f: &mut Frame, area: Rect, state: &CommitWizardState) {
+    let (color, headline) = if state.blocked {
+        (
+            Color::Red,
+            "🚫 Commit blocked: Sentinel found sensitive data in the message.",
+        )
+    } else {
+        (
+            Color::Yellow,
+            "⚠ Sentinel findings in the message (not blocking):",
+        )
+    };
+    let mut lines = vec![Line::styled(
+        headline,
+        Style::default().fg(color).add_modifier(Modifier::BOLD),
+    )];
+    for finding in &state.findings {
+        lines.push(Line::from(format!(
+            "  [{}] line {}: {} ({})",
+            finding.severity,
+            finding.line.unwrap_or(1),
+            finding.name,
+            finding.rule_id
+        )));
+        if let Some(excerpt) = &finding.excerpt {
+            lines.push(Line::styled(
+                format!("      {}", excerpt),
+                Style::default().fg(Color::Gray),
+            ));
+        }
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::styled(
+        if state.blocked {
+            "Press Backspace to go back and edit the message, Esc to cancel."
+        } else {
+            "Press Enter to COMMIT anyway. Press Backspace to go back, Esc to cancel."
+        },
+        Style::default().fg(Color::Gray),
+    ));
+
+    let findings = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(color))
+            .title(" Sentinel "),
+    );
+    f.render_widget(findings, area);
+}
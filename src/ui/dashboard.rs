@@ -1,12 +1,18 @@
 use crate::chronos::storage::{ChronosStore, SnapshotInfo}; // Import Chronos types
 use crate::config::Config;
+use crate::core::ignore_rules::IgnoreRules;
 use crate::core::GitRepository;
 use crate::features::impact_radar::{self, ImpactScore};
 use crate::features::interactive_rebase::{self, RebaseEntry};
 use crate::features::smart_context;
-use crate::sentinel::Sentinel;
+use crate::sentinel::finding::Suppression;
+use crate::sentinel::remediation;
+use crate::sentinel::validators::Validation;
+use crate::sentinel::{Category, Finding, Sentinel, Severity};
 use crate::ui::commit_wizard::CommitWizardState;
 use crate::ui::diff_viewer::{self, DiffState};
+use crate::ui::remediation::RemediationState;
+use crate::ui::scan_job::{ScanJob, ScanScope};
 use crate::ui::shelf::ShelfState;
 use crate::ui::zen_mode::ZenState;
 use crossterm::{
@@ -19,13 +25,17 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph}, // Added ListState
+    widgets::{Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph}, // Added ListState
     Terminal,
 };
 use std::path::Path;
+use std::sync::Arc;
 use std::{io, time::Duration};
 use tui_textarea::{Input, Key, TextArea}; // <--- Nueva Importación
 
+/// Timeline events fetched at a time by the Time Machine modal.
+const TIME_MACHINE_PAGE: usize = 50;
+
 pub fn run(config: Config, store: ChronosStore) -> Result<(), Box<dyn std::error::Error>> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -54,7 +64,9 @@ pub fn run(config: Config, store: ChronosStore) -> Result<(), Box<dyn std::error
 struct FileItem {
     path: String,
     status: String,
-    issues: Vec<String>,
+    findings: Vec<Finding>,
+    /// Set once Sentinel has looked at the file, so clean files get a badge too.
+    scanned: bool,
 }
 
 struct App<'a> {
@@ -65,7 +77,7 @@ struct App<'a> {
     selected_index: usize,
 
     // Core components
-    sentinel: Sentinel,
+    sentinel: Arc<Sentinel>,
     #[allow(dead_code)] // Keep config for future use if needed
     config: Config,
     chronos: ChronosStore, // New field
@@ -100,6 +112,12 @@ struct App<'a> {
 
     // Help Modal State
     show_help_modal: bool,
+
+    // Redaction Modal State
+    remediation: Option<RemediationState>,
+
+    // Background full scan
+    scan_job: Option<ScanJob>,
 }
 
 impl<'a> App<'a> {
@@ -113,7 +131,10 @@ impl<'a> App<'a> {
         let mut repo_opt = None;
 
         // Config is now passed in, no need to load it here.
-        let sentinel = Sentinel::new(&config);
+        let sentinel = Arc::new(Sentinel::new(&config));
+        for error in sentinel.config_errors() {
+            logs.push(format!("⚠️ Sentinel config: {}", error));
+        }
 
         match GitRepository::open(".") {
             Ok(mut repo) => {
@@ -126,7 +147,8 @@ impl<'a> App<'a> {
                             files.push(FileItem {
                                 path,
                                 status,
-                                issues: vec![],
+                                findings: vec![],
+                                scanned: false,
                             });
                         }
                     }
@@ -178,6 +200,8 @@ impl<'a> App<'a> {
             time_machine_events: vec![],
             time_machine_state: ListState::default(),
             show_help_modal: false,
+            remediation: None,
+            scan_job: None,
         }
     }
 
@@ -202,15 +226,109 @@ impl<'a> App<'a> {
     fn scan_selected(&mut self) {
         if let Some(file) = self.files.get_mut(self.selected_index) {
             let path = Path::new(&file.path);
-            match self.sentinel.scan_file(path) {
-                Ok(issues) => {
-                    file.issues = issues.clone();
+            if let Ok(findings) = self.sentinel.scan_file(path) {
+                file.findings = findings;
+                file.scanned = true;
+            }
+        }
+    }
+
+    /// Scans every changed (or tracked) file in the background, results
+    /// show up in the file list as they arrive.
+    fn start_full_scan(&mut self, scope: ScanScope) {
+        let Some(repo) = &self.repo else {
+            return;
+        };
+        let mut paths = match repo.changed_paths() {
+            Ok(paths) => paths,
+            Err(e) => {
+                self.logs.push(format!("Scan failed: {}", e));
+                return;
+            }
+        };
+        if scope == ScanScope::Tracked {
+            match repo.tracked_paths() {
+                Ok(tracked) => paths.extend(tracked),
+                Err(e) => {
+                    self.logs.push(format!("Scan failed: {}", e));
+                    return;
                 }
-                Err(_) => {}
+            }
+            paths.sort();
+            paths.dedup();
+        }
+        let sgitignore = IgnoreRules::sgitignore_only(repo.workdir().unwrap_or(Path::new(".")));
+        paths.retain(|p| Path::new(p).is_file() && !sgitignore.is_ignored(Path::new(p), false));
+
+        if let Some(job) = self.scan_job.take() {
+            job.cancel();
+        }
+        self.logs.push(format!(
+            "🔎 Scanning {} {} file(s)...",
+            paths.len(),
+            scope.label()
+        ));
+        self.scan_job = Some(ScanJob::start(self.sentinel.clone(), scope, paths));
+    }
+
+    fn cancel_scan(&mut self) {
+        if let Some(job) = &self.scan_job {
+            if !job.is_cancelled() {
+                job.cancel();
+                self.logs.push("Cancelling scan...".to_string());
             }
         }
     }
 
+    /// Moves finished results from the scan job into the file list.
+    fn poll_scan(&mut self) {
+        let Some(mut job) = self.scan_job.take() else {
+            return;
+        };
+        for result in job.poll() {
+            match result.findings {
+                Ok(findings) => self.show_findings(result.path, findings),
+                Err(e) => self
+                    .logs
+                    .push(format!("Scan error in {}: {}", result.path, e)),
+            }
+        }
+        if !job.is_finished() {
+            self.scan_job = Some(job);
+            return;
+        }
+        let outcome = if job.is_cancelled() {
+            "cancelled"
+        } else {
+            "complete"
+        };
+        self.logs.push(format!(
+            "🔎 Scan {}: {}/{} {} file(s), {} with findings, {} error(s) in {:.1}s",
+            outcome,
+            job.scanned,
+            job.total,
+            job.scope.label(),
+            job.flagged,
+            job.errors,
+            job.elapsed().as_secs_f64()
+        ));
+    }
+
+    /// Unchanged tracked files are only listed when they have something to show.
+    fn show_findings(&mut self, path: String, findings: Vec<Finding>) {
+        if let Some(file) = self.files.iter_mut().find(|f| f.path == path) {
+            file.findings = findings;
+            file.scanned = true;
+        } else if findings.iter().any(|f| !f.is_suppressed()) {
+            self.files.push(FileItem {
+                path,
+                status: "Tracked".to_string(),
+                findings,
+                scanned: true,
+            });
+        }
+    }
+
     fn open_commit_modal(&mut self) {
         self.commit_wizard_active = true;
         self.commit_wizard_state.reset();
@@ -240,34 +358,79 @@ impl<'a> App<'a> {
                     self.files.push(FileItem {
                         path,
                         status,
-                        issues: vec![],
+                        findings: vec![],
+                        scanned: false,
                     });
                 }
             }
         }
     }
 
+    /// Scans the formatted message for the wizard's confirmation step.
+    fn check_commit_message(&mut self) {
+        let message = self.commit_wizard_state.format_commit_message();
+        let findings = self
+            .sentinel
+            .scan_text(crate::sentinel::COMMIT_MESSAGE, &message);
+        self.commit_wizard_state.blocked = self.sentinel.blocks(&findings);
+        self.commit_wizard_state.findings = findings;
+    }
+
     fn perform_commit(&mut self) {
+        self.check_commit_message();
         if let Some(repo) = &self.repo {
             let message = self.commit_wizard_state.format_commit_message();
 
             if message.trim().is_empty() {
                 self.logs
                     .push("❌ Commit abortado: Mensaje vacío.".to_string());
-            } else {
-                match repo.commit(&message) {
-                    Ok(oid) => {
-                        self.logs.push(format!(
-                            "🚀 Commit exitoso: {} - {}",
-                            &oid.to_string()[..7],
-                            message
-                        ));
-                        self.commit_wizard_active = false;
-                        self.commit_wizard_state.reset();
-                        self.refresh_status(); // Recargar status completo
+                return;
+            }
+
+            // The confirmation step explains why, the wizard stays open to fix it
+            if self.commit_wizard_state.blocked {
+                self.logs
+                    .push("🚫 Commit abortado: el mensaje contiene datos sensibles.".to_string());
+                return;
+            }
+
+            // Re-verify what is actually in the index: hunk staging means it
+            // can differ from the working-tree files scanned while navigating.
+            match self.sentinel.scan_index(repo) {
+                Ok(findings) if self.sentinel.blocks(&findings) => {
+                    self.logs.push(
+                        "🚫 Commit abortado: el índice contiene riesgos de seguridad.".to_string(),
+                    );
+                    for finding in findings
+                        .iter()
+                        .filter(|f| f.severity >= self.sentinel.fail_on())
+                    {
+                        self.logs.push(format!("   {}", finding));
                     }
-                    Err(e) => self.logs.push(format!("❌ Error en commit: {}", e)),
+                    return;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    self.logs.push(format!(
+                        "❌ Commit abortado: no se pudo verificar el índice: {}",
+                        e
+                    ));
+                    return;
+                }
+            }
+
+            match repo.commit(&message) {
+                Ok(oid) => {
+                    self.logs.push(format!(
+                        "🚀 Commit exitoso: {} - {}",
+                        &oid.to_string()[..7],
+                        message
+                    ));
+                    self.commit_wizard_active = false;
+                    self.commit_wizard_state.reset();
+                    self.refresh_status(); // Recargar status completo
                 }
+                Err(e) => self.logs.push(format!("❌ Error en commit: {}", e)),
             }
         }
     }
@@ -320,7 +483,7 @@ impl<'a> App<'a> {
     }
 
     fn open_time_machine(&mut self) {
-        match self.chronos.get_global_timeline(50) {
+        match self.chronos.get_global_timeline(TIME_MACHINE_PAGE) {
             Ok(events) => {
                 self.time_machine_events = events;
                 self.show_time_machine_modal = true;
@@ -332,6 +495,16 @@ impl<'a> App<'a> {
                     "Loaded {} global timeline events. Welcome to Ghost Branches.",
                     self.time_machine_events.len()
                 ));
+                if let Ok(stats) = self.chronos.stats() {
+                    self.logs.push(format!(
+                        "Chronos: {} snapshots, {} KiB stored ({} keyframes, {} deltas), ratio {:.1}x",
+                        stats.snapshots,
+                        stats.stored_bytes / 1024,
+                        stats.full_blobs,
+                        stats.delta_blobs,
+                        stats.ratio()
+                    ));
+                }
             }
             Err(e) => {
                 self.logs.push(format!("Error loading Time Machine: {}", e));
@@ -339,6 +512,27 @@ impl<'a> App<'a> {
         }
     }
 
+    /// Appends the next page of older events, false when there are none.
+    fn load_more_time_machine(&mut self) -> bool {
+        let Some((timestamp, path)) = self.time_machine_events.last() else {
+            return false;
+        };
+        match self
+            .chronos
+            .get_timeline_page(Some((*timestamp, path)), TIME_MACHINE_PAGE)
+        {
+            Ok(events) if !events.is_empty() => {
+                self.time_machine_events.extend(events);
+                true
+            }
+            Ok(_) => false,
+            Err(e) => {
+                self.logs.push(format!("Error loading Time Machine: {}", e));
+                false
+            }
+        }
+    }
+
     fn restore_time_machine(&mut self) {
         if let Some(selected) = self.time_machine_state.selected() {
             if let Some((timestamp, _)) = self.time_machine_events.get(selected) {
@@ -391,6 +585,86 @@ impl<'a> App<'a> {
         }
     }
 
+    /// Opens the redaction preview for the most severe finding of the selected file.
+    fn open_remediation(&mut self) {
+        let Some(file) = self.files.get(self.selected_index) else {
+            return;
+        };
+        let content = match std::fs::read_to_string(&file.path) {
+            Ok(content) => content,
+            Err(e) => {
+                self.logs
+                    .push(format!("Cannot redact {}: {}", file.path, e));
+                return;
+            }
+        };
+        // Fresh scan so the span matches what is on disk right now
+        let finding = self
+            .sentinel
+            .scan_bytes(&file.path, content.as_bytes())
+            .into_iter()
+            .filter(|f| !f.is_suppressed() && f.span.is_some())
+            .min_by_key(|f| std::cmp::Reverse(f.severity));
+        match finding {
+            Some(finding) => {
+                self.remediation = Some(RemediationState::new(&file.path, content, finding))
+            }
+            None => self
+                .logs
+                .push(format!("Nothing to redact in {}.", file.path)),
+        }
+    }
+
+    fn handle_remediation_key(&mut self, key: Key) {
+        let Some(state) = self.remediation.as_mut() else {
+            return;
+        };
+        match key {
+            Key::Esc => self.remediation = None,
+            Key::Enter => self.apply_remediation(),
+            Key::Char('r') => state.toggle_replacement(),
+            Key::Char('n') => state.toggle_env_example(),
+            Key::Char('g') => state.toggle_gitignore(),
+            Key::Up => state.diff.prev_hunk(),
+            Key::Down => state.diff.next_hunk(),
+            _ => {}
+        }
+    }
+
+    fn apply_remediation(&mut self) {
+        let Some(state) = self.remediation.take() else {
+            return;
+        };
+        let Some(plan) = &state.plan else {
+            self.remediation = Some(state);
+            return;
+        };
+        if let Err(e) = remediation::apply(plan) {
+            self.logs.push(format!("❌ Redaction failed: {}", e));
+            self.remediation = Some(state);
+            return;
+        }
+
+        self.logs.push(format!(
+            "🔧 {} in {} replaced with {}",
+            state.finding.rule_id,
+            state.path,
+            state.options.replacement.render(&plan.var_name)
+        ));
+        let untracked = self
+            .files
+            .iter()
+            .any(|f| f.path == state.path && f.status.contains("WT_NEW"));
+        if state.options.gitignore && !untracked {
+            self.logs.push(format!(
+                "   {} is still tracked: run `git rm --cached {}` to stop committing it.",
+                state.path, state.path
+            ));
+        }
+        self.refresh_status();
+        self.scan_selected();
+    }
+
     fn stage_selected_hunk(&mut self) {
         if self.diff_state.hunks.is_empty() {
             return;
@@ -407,16 +681,41 @@ impl<'a> App<'a> {
         // However, `GitRepository` is owned by `App` inside `Option<GitRepository>`.
 
         if let Some(repo) = &self.repo {
-            match repo.apply_patch(&hunk.patch) {
-                Ok(_) => {
-                    self.logs.push("Hunk staged successfully.".to_string());
-                    // Refresh diff to remove the staged hunk from view (or update it)
-                    self.load_diff();
-                }
-                Err(e) => {
-                    self.logs.push(format!("Failed to stage hunk: {}", e));
+            if let Err(e) = repo.apply_patch(&hunk.patch) {
+                self.logs.push(format!("Failed to stage hunk: {}", e));
+                return;
+            }
+
+            // Scan the staged blob, the working-tree file may hold other hunks
+            let path = match self.files.get(self.selected_index) {
+                Some(file) => file.path.clone(),
+                None => return,
+            };
+            match self.sentinel.scan_index_entry(repo, &path) {
+                Ok(findings) if self.sentinel.blocks(&findings) => {
+                    let reason = findings
+                        .iter()
+                        .find(|f| f.severity >= self.sentinel.fail_on())
+                        .map(|f| f.to_string())
+                        .unwrap_or_default();
+                    match repo.unapply_patch(&hunk.patch) {
+                        Ok(_) => self
+                            .logs
+                            .push(format!("🚫 BLOQUEADO: hunk no staged. {}", reason)),
+                        Err(e) => self.logs.push(format!(
+                            "🚫 {} — failed to unstage hunk, commit will be blocked: {}",
+                            reason, e
+                        )),
+                    }
+                    return;
                 }
+                Ok(_) => self.logs.push("Hunk staged successfully.".to_string()),
+                Err(e) => self
+                    .logs
+                    .push(format!("Hunk staged, but the index scan failed: {}", e)),
             }
+            // Refresh diff to remove the staged hunk from view (or update it)
+            self.load_diff();
         }
     }
 }
@@ -426,6 +725,7 @@ fn run_app<B: ratatui::backend::Backend>(
     app: &mut App,
 ) -> io::Result<()> {
     loop {
+        app.poll_scan();
         terminal.draw(|f| ui(f, app))?;
 
         if crossterm::event::poll(Duration::from_millis(250))? {
@@ -434,6 +734,7 @@ fn run_app<B: ratatui::backend::Backend>(
                     key: Key::Char('q'),
                     ..
                 } if !app.commit_wizard_active
+                    && app.remediation.is_none()
                     && !app.show_history_modal
                     && !app.show_diff_modal
                     && !app.show_time_machine_modal =>
@@ -451,18 +752,22 @@ fn run_app<B: ratatui::backend::Backend>(
                         app.logs.push("Commit wizard cancelled.".to_string());
                     } else {
                         let key = input.key;
-                        if app.commit_wizard_state.handle_input(input) {
-                            // Check ONLY if we just pressed Enter on Confirmation
-                            // Note: handle_input returns true if it handled a navigation key (like Enter)
-                            if key == Key::Enter
-                                && app.commit_wizard_state.step == WizardStep::Confirmation
-                            {
+                        let confirming = app.commit_wizard_state.step == WizardStep::Confirmation;
+                        // Note: handle_input returns true if it handled a navigation key (like Enter)
+                        if app.commit_wizard_state.handle_input(input) && key == Key::Enter {
+                            if confirming {
+                                // Enter pressed on Confirmation itself
                                 app.perform_commit();
+                            } else if app.commit_wizard_state.step == WizardStep::Confirmation {
+                                app.check_commit_message();
                             }
                         }
                     }
                 }
 
+                // Lógica del Modal de Redacción
+                input if app.remediation.is_some() => app.handle_remediation_key(input.key),
+
                 // Lógica del Modal de Historial
                 Input { key: Key::Esc, .. } if app.show_history_modal => {
                     app.show_history_modal = false;
@@ -472,35 +777,35 @@ fn run_app<B: ratatui::backend::Backend>(
                 } if app.show_history_modal => {
                     app.restore_snapshot();
                 }
-                Input { key: Key::Down, .. } if app.show_history_modal => {
-                    if !app.history_items.is_empty() {
-                        let i = match app.history_state.selected() {
-                            Some(i) => {
-                                if i >= app.history_items.len() - 1 {
-                                    0
-                                } else {
-                                    i + 1
-                                }
+                Input { key: Key::Down, .. }
+                    if app.show_history_modal && !app.history_items.is_empty() =>
+                {
+                    let i = match app.history_state.selected() {
+                        Some(i) => {
+                            if i >= app.history_items.len() - 1 {
+                                0
+                            } else {
+                                i + 1
                             }
-                            None => 0,
-                        };
-                        app.history_state.select(Some(i));
-                    }
+                        }
+                        None => 0,
+                    };
+                    app.history_state.select(Some(i));
                 }
-                Input { key: Key::Up, .. } if app.show_history_modal => {
-                    if !app.history_items.is_empty() {
-                        let i = match app.history_state.selected() {
-                            Some(i) => {
-                                if i == 0 {
-                                    app.history_items.len() - 1
-                                } else {
-                                    i - 1
-                                }
+                Input { key: Key::Up, .. }
+                    if app.show_history_modal && !app.history_items.is_empty() =>
+                {
+                    let i = match app.history_state.selected() {
+                        Some(i) => {
+                            if i == 0 {
+                                app.history_items.len() - 1
+                            } else {
+                                i - 1
                             }
-                            None => 0,
-                        };
-                        app.history_state.select(Some(i));
-                    }
+                        }
+                        None => 0,
+                    };
+                    app.history_state.select(Some(i));
                 }
 
                 // Lógica del Modal de Diff
@@ -530,41 +835,55 @@ fn run_app<B: ratatui::backend::Backend>(
                 } if app.show_time_machine_modal => {
                     app.restore_time_machine();
                 }
-                Input { key: Key::Down, .. } if app.show_time_machine_modal => {
-                    if !app.time_machine_events.is_empty() {
-                        let i = match app.time_machine_state.selected() {
-                            Some(i) => {
-                                if i >= app.time_machine_events.len() - 1 {
-                                    0
-                                } else {
-                                    i + 1
-                                }
+                Input { key: Key::Down, .. }
+                    if app.show_time_machine_modal && !app.time_machine_events.is_empty() =>
+                {
+                    let i = match app.time_machine_state.selected() {
+                        Some(i) => {
+                            // Older events are fetched on reaching the end
+                            if i >= app.time_machine_events.len() - 1
+                                && !app.load_more_time_machine()
+                            {
+                                0
+                            } else {
+                                i + 1
                             }
-                            None => 0,
-                        };
-                        app.time_machine_state.select(Some(i));
-                    }
+                        }
+                        None => 0,
+                    };
+                    app.time_machine_state.select(Some(i));
                 }
-                Input { key: Key::Up, .. } if app.show_time_machine_modal => {
-                    if !app.time_machine_events.is_empty() {
-                        let i = match app.time_machine_state.selected() {
-                            Some(i) => {
-                                if i == 0 {
-                                    app.time_machine_events.len() - 1
-                                } else {
-                                    i - 1
-                                }
+                Input { key: Key::Up, .. }
+                    if app.show_time_machine_modal && !app.time_machine_events.is_empty() =>
+                {
+                    let i = match app.time_machine_state.selected() {
+                        Some(i) => {
+                            if i == 0 {
+                                app.time_machine_events.len() - 1
+                            } else {
+                                i - 1
                             }
-                            None => 0,
-                        };
-                        app.time_machine_state.select(Some(i));
-                    }
+                        }
+                        None => 0,
+                    };
+                    app.time_machine_state.select(Some(i));
                 }
                 _ if app.show_time_machine_modal => {}
 
                 // Block other inputs when history/diff modal is open
                 _ if app.show_history_modal => {}
 
+                // Escaneo completo en segundo plano
+                Input {
+                    key: Key::Char('a'),
+                    ..
+                } => app.start_full_scan(ScanScope::Changed),
+                Input {
+                    key: Key::Char('A'),
+                    ..
+                } => app.start_full_scan(ScanScope::Tracked),
+                Input { key: Key::Esc, .. } if app.scan_job.is_some() => app.cancel_scan(),
+
                 // Lógica Normal (Navegación)
                 Input { key: Key::Down, .. } => app.next(),
                 Input { key: Key::Up, .. } => app.previous(),
@@ -588,12 +907,18 @@ fn run_app<B: ratatui::backend::Backend>(
                     key: Key::Char('t'),
                     ..
                 } => app.open_time_machine(),
+                Input {
+                    key: Key::Char('x'),
+                    ..
+                } => app.open_remediation(),
 
                 Input {
                     key: Key::Char(' '),
                     ..
                 } => {
                     // STAGE/UNSTAGE INTELIGENTE
+                    // Fresh scan: the file may have changed since it was selected
+                    app.scan_selected();
                     if let Some(repo) = &app.repo {
                         if let Some(file) = app.files.get_mut(app.selected_index) {
                             if file.status.contains("Index") || file.status == "Staged" {
@@ -606,7 +931,7 @@ fn run_app<B: ratatui::backend::Backend>(
                                 }
                             } else {
                                 // STAGE
-                                if !file.issues.is_empty() {
+                                if app.sentinel.blocks(&file.findings) {
                                     app.logs.push(format!(
                                         "🚫 BLOQUEADO: {} tiene riesgos de seguridad.",
                                         file.path
@@ -658,7 +983,13 @@ fn ui(f: &mut ratatui::Frame, app: &mut App) {
                     .map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string())
                     .unwrap_or_else(|| "Unknown".to_string());
 
-                ListItem::new(format!("{} - Size: {} bytes", time_str, snap.size))
+                match snap.change_set {
+                    Some(id) => ListItem::new(format!(
+                        "{} - Size: {} bytes - Change set #{}",
+                        time_str, snap.size, id
+                    )),
+                    None => ListItem::new(format!("{} - Size: {} bytes", time_str, snap.size)),
+                }
             })
             .collect();
 
@@ -719,6 +1050,12 @@ fn ui(f: &mut ratatui::Frame, app: &mut App) {
         diff_viewer::render_diff(f, area, &mut app.diff_state);
     }
 
+    if let Some(state) = &mut app.remediation {
+        let area = centered_rect(80, 80, f.size());
+        f.render_widget(Clear, area);
+        crate::ui::remediation::render(f, area, state);
+    }
+
     if app.show_help_modal {
         let area = centered_rect(60, 60, f.size());
         f.render_widget(Clear, area);
@@ -745,8 +1082,12 @@ fn ui(f: &mut ratatui::Frame, app: &mut App) {
             Line::from("  d      : View Diff (Interactive Staging)"),
             Line::from("    Use ↑/↓ to select hunk, 's' to stage hunk"),
             Line::from("  c      : Commit (Wizard)"),
+            Line::from("    Enter: next step, Backspace on empty field: back"),
             Line::from("  h      : File History"),
             Line::from("  t      : Time Machine (Ghost Branches)"),
+            Line::from("  x      : Redact Secret (preview before writing)"),
+            Line::from("    'r' env var/placeholder, 'n' .env.example, 'g' .gitignore"),
+            Line::from("  a / A  : Scan all changed / tracked files (Esc cancels)"),
             Line::from("  z      : Toggle Zen Mode"),
             Line::from(""),
             Line::from(Span::styled(
@@ -769,18 +1110,7 @@ fn ui(f: &mut ratatui::Frame, app: &mut App) {
 
 // Funciones auxiliares de renderizado para mantener el código limpio
 fn render_zen_mode(f: &mut ratatui::Frame, app: &mut App) {
-    let items: Vec<ListItem> = app
-        .files
-        .iter()
-        .map(|i| {
-            let style = if !i.issues.is_empty() {
-                Style::default().fg(Color::Red)
-            } else {
-                Style::default()
-            };
-            ListItem::new(format!("{} [{}]", i.path, i.status)).style(style)
-        })
-        .collect();
+    let items: Vec<ListItem> = app.files.iter().map(file_list_item).collect();
     f.render_stateful_widget(
         List::new(items).block(
             Block::default()
@@ -792,6 +1122,58 @@ fn render_zen_mode(f: &mut ratatui::Frame, app: &mut App) {
     );
 }
 
+/// `path [status]`, then one badge per severity with the number of
+/// unsuppressed findings, or a check mark for scanned clean files.
+fn file_list_item(item: &FileItem) -> ListItem<'_> {
+    let active: Vec<&Finding> = item
+        .findings
+        .iter()
+        .filter(|f| !f.is_suppressed())
+        .collect();
+    let style = if active.is_empty() {
+        Style::default()
+    } else {
+        Style::default().fg(Color::Red)
+    };
+    let mut spans = vec![Span::raw(format!("{} [{}]", item.path, item.status))];
+    for (severity, symbol, color) in [
+        (Severity::Block, "✖", Color::Red),
+        (Severity::Warn, "▲", Color::Yellow),
+        (Severity::Info, "●", Color::Blue),
+    ] {
+        let count = active.iter().filter(|f| f.severity == severity).count();
+        if count > 0 {
+            spans.push(Span::styled(
+                format!(" {}{}", symbol, count),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ));
+        }
+    }
+    if item.scanned && active.is_empty() {
+        spans.push(Span::styled(" ✓", Style::default().fg(Color::Green)));
+    }
+    ListItem::new(Line::from(spans)).style(style)
+}
+
+fn scan_gauge(job: &ScanJob) -> Gauge<'_> {
+    let title = if job.is_cancelled() {
+        " Sentinel scan (cancelling) "
+    } else {
+        " Sentinel scan (Esc to cancel) "
+    };
+    Gauge::default()
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .gauge_style(Style::default().fg(Color::Cyan))
+        .ratio(job.ratio())
+        .label(format!(
+            "{}/{} {} files, {} with findings",
+            job.scanned,
+            job.total,
+            job.scope.label(),
+            job.flagged
+        ))
+}
+
 fn render_dashboard(f: &mut ratatui::Frame, app: &mut App) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -799,18 +1181,19 @@ fn render_dashboard(f: &mut ratatui::Frame, app: &mut App) {
         .split(f.size());
 
     // Lista de archivos
-    let items: Vec<ListItem> = app
-        .files
-        .iter()
-        .map(|i| {
-            let style = if !i.issues.is_empty() {
-                Style::default().fg(Color::Red)
-            } else {
-                Style::default()
-            };
-            ListItem::new(format!("{} [{}]", i.path, i.status)).style(style)
-        })
-        .collect();
+    let items: Vec<ListItem> = app.files.iter().map(file_list_item).collect();
+
+    let list_area = match &app.scan_job {
+        Some(job) => {
+            let left = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(3), Constraint::Length(3)])
+                .split(chunks[0]);
+            f.render_widget(scan_gauge(job), left[1]);
+            left[0]
+        }
+        None => chunks[0],
+    };
 
     let mut state = ratatui::widgets::ListState::default();
     state.select(Some(app.selected_index));
@@ -818,7 +1201,7 @@ fn render_dashboard(f: &mut ratatui::Frame, app: &mut App) {
         List::new(items)
             .block(Block::default().borders(Borders::ALL).title("Files"))
             .highlight_symbol(">> "),
-        chunks[0],
+        list_area,
         &mut state,
     );
 
@@ -826,8 +1209,8 @@ fn render_dashboard(f: &mut ratatui::Frame, app: &mut App) {
     let right_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Percentage(40),
-            Constraint::Percentage(20),
+            Constraint::Percentage(30),
+            Constraint::Percentage(30),
             Constraint::Percentage(20),
             Constraint::Percentage(20),
         ])
@@ -839,13 +1222,9 @@ fn render_dashboard(f: &mut ratatui::Frame, app: &mut App) {
         right_chunks[0],
     );
 
-    let info = format!(
-        "Impact: {:?}\nScope: {}",
-        app.impact_score.as_ref().map(|s| s.score).unwrap_or(0.0),
-        app.smart_prefix
-    );
     f.render_widget(
-        Paragraph::new(info).block(Block::default().borders(Borders::ALL).title("Analysis")),
+        Paragraph::new(Text::from(analysis_lines(app)))
+            .block(Block::default().borders(Borders::ALL).title("Analysis")),
         right_chunks[1],
     );
 
@@ -874,6 +1253,130 @@ fn render_dashboard(f: &mut ratatui::Frame, app: &mut App) {
     f.render_widget(rebase_list, right_chunks[3]);
 }
 
+fn analysis_lines<'b>(app: &'b App) -> Vec<Line<'b>> {
+    let mut lines = vec![
+        Line::from(format!(
+            "Impact: {:?}",
+            app.impact_score.as_ref().map(|s| s.score).unwrap_or(0.0)
+        )),
+        Line::from(format!("Scope: {}", app.smart_prefix)),
+    ];
+
+    let Some(file) = app.files.get(app.selected_index) else {
+        return lines;
+    };
+    let policy = app.sentinel.policy(&file.path);
+    if policy.matched.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "Policy: default",
+            Style::default().add_modifier(Modifier::DIM),
+        )));
+    } else {
+        lines.push(Line::from(format!(
+            "Policy: overrides for {}",
+            policy.matched.join(" + ")
+        )));
+        for detail in policy.describe(app.sentinel.binary_extensions()) {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", detail),
+                Style::default().add_modifier(Modifier::DIM),
+            )));
+        }
+    }
+    let baselined = file
+        .findings
+        .iter()
+        .filter(|f| f.suppressed == Some(Suppression::Baseline))
+        .count();
+    if baselined > 0 {
+        lines.push(Line::from(Span::styled(
+            format!("Sentinel: {} baselined finding(s) hidden", baselined),
+            Style::default().add_modifier(Modifier::DIM),
+        )));
+    }
+    if baselined == file.findings.len() {
+        lines.push(Line::from(Span::styled(
+            "Sentinel: no findings",
+            Style::default().fg(Color::Green),
+        )));
+        return lines;
+    }
+
+    for finding in file
+        .findings
+        .iter()
+        .filter(|f| f.suppressed != Some(Suppression::Baseline))
+    {
+        let color = match finding.severity {
+            Severity::Block => Color::Red,
+            Severity::Warn => Color::Yellow,
+            Severity::Info => Color::Blue,
+        };
+        let location = match (finding.line, finding.column) {
+            (Some(line), Some(col)) => format!("{}:{} ", line, col),
+            _ => String::new(),
+        };
+        if let Some(suppression) = &finding.suppressed {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "[allowed] {}{} ({}) — {}",
+                    location, finding.name, finding.rule_id, suppression
+                ),
+                Style::default().add_modifier(Modifier::DIM),
+            )));
+            continue;
+        }
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("[{}] ", finding.severity),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ),
+            match finding.category {
+                Category::Pii => Span::styled("[pii] ", Style::default().fg(Color::Magenta)),
+                _ => Span::raw(""),
+            },
+            Span::raw(format!(
+                "{}{} ({})",
+                location, finding.name, finding.rule_id
+            )),
+            match finding.validation {
+                Some(Validation::VerifiedFormat) => Span::styled(
+                    " ✓ verified format",
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                ),
+                Some(Validation::LikelyPlaceholder) => Span::styled(
+                    " likely placeholder",
+                    Style::default().add_modifier(Modifier::DIM),
+                ),
+                None => Span::raw(""),
+            },
+        ]));
+        if let Some(excerpt) = &finding.excerpt {
+            lines.push(Line::from(Span::styled(
+                format!("    {}", excerpt),
+                Style::default().fg(Color::Gray),
+            )));
+        }
+        if let Some(remediation) = &finding.remediation {
+            lines.push(Line::from(Span::styled(
+                format!("    → {}", remediation),
+                Style::default().fg(Color::Cyan),
+            )));
+        }
+    }
+    if file
+        .findings
+        .iter()
+        .any(|f| !f.is_suppressed() && f.span.is_some() && f.path == file.path)
+    {
+        lines.push(Line::from(Span::styled(
+            "Press 'x' to redact",
+            Style::default().add_modifier(Modifier::DIM),
+        )));
+    }
+    lines
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
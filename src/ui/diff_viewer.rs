@@ -14,6 +14,7 @@ pub struct Hunk {
     pub lines: Vec<(ChangeTag, String)>,
 }
 
+#[derive(Default)]
 pub struct DiffState {
     pub scroll: u16,
     pub max_scroll: u16,
@@ -21,17 +22,6 @@ pub struct DiffState {
     pub hunks: Vec<Hunk>,
 }
 
-impl Default for DiffState {
-    fn default() -> Self {
-        Self {
-            scroll: 0,
-            max_scroll: 0,
-            selected_hunk: 0,
-            hunks: Vec::new(),
-        }
-    }
-}
-
 impl DiffState {
     pub fn next_hunk(&mut self) {
         if !self.hunks.is_empty() && self.selected_hunk < self.hunks.len() - 1 {
@@ -200,11 +190,7 @@ pub fn render_diff(f: &mut Frame, area: Rect, state: &mut DiffState) {
     let line_count = diff_text.lines.len() as u16;
     let height = area.height.saturating_sub(2);
 
-    state.max_scroll = if line_count > height {
-        line_count - height
-    } else {
-        0
-    };
+    state.max_scroll = line_count.saturating_sub(height);
 
     if let Some((start, end)) = scroll_anchor {
         // Ensure the hunk is visible
@@ -2,6 +2,8 @@
 pub mod commit_wizard;
 pub mod dashboard;
 pub mod diff_viewer;
+pub mod remediation;
+pub mod scan_job;
 pub mod shelf;
 pub mod zen_mode;
 
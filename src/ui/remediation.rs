@@ -0,0 +1,143 @@
+use crate::sentinel::remediation::{self, Options, Plan, Replacement};
+use crate::sentinel::Finding;
+use crate::ui::diff_viewer::{self, DiffState};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use std::path::Path;
+
+/// Redaction of one finding, previewed as a diff before anything is written.
+pub struct RemediationState {
+    pub path: String,
+    pub finding: Finding,
+    content: String,
+    pub options: Options,
+    pub plan: Option<Plan>,
+    pub error: Option<String>,
+    pub diff: DiffState,
+}
+
+impl RemediationState {
+    pub fn new(path: &str, content: String, finding: Finding) -> Self {
+        let mut state = Self {
+            path: path.to_string(),
+            finding,
+            content,
+            options: Options::default(),
+            plan: None,
+            error: None,
+            diff: DiffState::default(),
+        };
+        state.rebuild();
+        state
+    }
+
+    pub fn toggle_replacement(&mut self) {
+        self.options.replacement = self.options.replacement.toggle();
+        self.rebuild();
+    }
+
+    pub fn toggle_env_example(&mut self) {
+        self.options.env_example = !self.options.env_example;
+        self.rebuild();
+    }
+
+    pub fn toggle_gitignore(&mut self) {
+        self.options.gitignore = !self.options.gitignore;
+        self.rebuild();
+    }
+
+    /// Recomputes the plan and its preview after an option changed.
+    fn rebuild(&mut self) {
+        match remediation::plan(
+            Path::new("."),
+            &self.path,
+            &self.content,
+            &self.finding,
+            &self.options,
+        ) {
+            Ok(plan) => {
+                self.diff.hunks = plan
+                    .edits
+                    .iter()
+                    .flat_map(|edit| {
+                        let display = edit
+                            .path
+                            .strip_prefix(".")
+                            .unwrap_or(&edit.path)
+                            .to_string_lossy()
+                            .to_string();
+                        let mut hunks = diff_viewer::compute_hunks(&edit.old, &edit.new, &display);
+                        for hunk in &mut hunks {
+                            hunk.header = format!("{} {}", display, hunk.header);
+                        }
+                        hunks
+                    })
+                    .collect();
+                self.diff.selected_hunk = 0;
+                self.diff.scroll = 0;
+                self.plan = Some(plan);
+                self.error = None;
+            }
+            Err(e) => {
+                self.diff = DiffState::default();
+                self.plan = None;
+                self.error = Some(e.to_string());
+            }
+        }
+    }
+}
+
+pub fn render(f: &mut Frame, area: Rect, state: &mut RemediationState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(7), Constraint::Min(3)])
+        .split(area);
+
+    let check = |on: bool| if on { "[x]" } else { "[ ]" };
+    let replacement = match state.options.replacement {
+        Replacement::EnvVar => "environment variable",
+        Replacement::Placeholder => "placeholder",
+    };
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(
+                format!("{} ", state.finding.name),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(state.finding.location()),
+        ]),
+        Line::from(format!("  r  Replace with: {}", replacement)),
+        Line::from(format!(
+            "  n  {} Add key to {}",
+            check(state.options.env_example),
+            remediation::ENV_EXAMPLE_FILE
+        )),
+        Line::from(format!(
+            "  g  {} Add {} to {}",
+            check(state.options.gitignore),
+            state.path,
+            remediation::GITIGNORE_FILE
+        )),
+    ];
+    if let Some(error) = &state.error {
+        lines.push(Line::from(Span::styled(
+            error.as_str(),
+            Style::default().fg(Color::Red),
+        )));
+    }
+    f.render_widget(
+        Paragraph::new(Text::from(lines)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" 🔧 Redact Secret (Enter to Apply / Esc to Cancel) "),
+        ),
+        chunks[0],
+    );
+
+    diff_viewer::render_diff(f, chunks[1], &mut state.diff);
+}
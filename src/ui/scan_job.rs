@@ -0,0 +1,142 @@
+use crate::sentinel::{Finding, Sentinel};
+use rayon::prelude::*;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Which files a full scan covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanScope {
+    /// Files with uncommitted changes, untracked ones included.
+    Changed,
+    /// Changed files plus every file in the index.
+    Tracked,
+}
+
+impl ScanScope {
+    pub fn label(self) -> &'static str {
+        match self {
+            ScanScope::Changed => "changed",
+            ScanScope::Tracked => "tracked",
+        }
+    }
+}
+
+/// Result of scanning one file, `Err` holds the read error.
+pub struct ScanResult {
+    pub path: String,
+    pub findings: Result<Vec<Finding>, String>,
+}
+
+enum Event {
+    File(ScanResult),
+    Finished,
+}
+
+/// A whole-changeset Sentinel scan running on the rayon pool, so the TUI
+/// keeps drawing while files are read. Results are streamed back through a
+/// channel and collected with `poll` on every frame.
+pub struct ScanJob {
+    pub scope: ScanScope,
+    pub total: usize,
+    pub scanned: usize,
+    /// Scanned files with at least one unsuppressed finding.
+    pub flagged: usize,
+    pub errors: usize,
+    started: Instant,
+    cancel: Arc<AtomicBool>,
+    events: Receiver<Event>,
+    finished: bool,
+}
+
+impl ScanJob {
+    pub fn start(sentinel: Arc<Sentinel>, scope: ScanScope, paths: Vec<String>) -> Self {
+        let (sender, events) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let total = paths.len();
+        let flag = cancel.clone();
+        thread::spawn(move || scan_all(&sentinel, &paths, &flag, sender));
+        Self {
+            scope,
+            total,
+            scanned: 0,
+            flagged: 0,
+            errors: 0,
+            started: Instant::now(),
+            cancel,
+            events,
+            finished: false,
+        }
+    }
+
+    /// Files still being read finish, nothing new is started.
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    /// True once the workers are done, cancelled or not.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Fraction of files scanned, for the progress bar.
+    pub fn ratio(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.scanned as f64 / self.total as f64
+        }
+    }
+
+    /// Results that arrived since the last call. Never blocks.
+    pub fn poll(&mut self) -> Vec<ScanResult> {
+        let mut results = Vec::new();
+        for event in self.events.try_iter() {
+            match event {
+                Event::File(result) => {
+                    self.scanned += 1;
+                    match &result.findings {
+                        Ok(findings) if findings.iter().any(|f| !f.is_suppressed()) => {
+                            self.flagged += 1
+                        }
+                        Ok(_) => {}
+                        Err(_) => self.errors += 1,
+                    }
+                    results.push(result);
+                }
+                Event::Finished => self.finished = true,
+            }
+        }
+        results
+    }
+}
+
+fn scan_all(sentinel: &Sentinel, paths: &[String], cancel: &AtomicBool, sender: Sender<Event>) {
+    paths
+        .par_iter()
+        .for_each_with(sender.clone(), |sender, path| {
+            if cancel.load(Ordering::Relaxed) {
+                return;
+            }
+            let findings = sentinel
+                .scan_file(Path::new(path))
+                .map_err(|e| e.to_string());
+            // The TUI may have quit, nobody is listening any more
+            let _ = sender.send(Event::File(ScanResult {
+                path: path.clone(),
+                findings,
+            }));
+        });
+    let _ = sender.send(Event::Finished);
+}
@@ -1,5 +1,6 @@
 use crate::core::GitRepository;
 
+#[derive(Default)]
 pub struct ShelfState {
     pub stashes: Vec<String>,
 }